@@ -32,6 +32,59 @@ async fn handle_request(req: Request<Incoming>) -> Result<Response<BoxBody>, Gen
                 .header("Content-Type", "text/plain; charset=utf-8")
                 .body(full(Bytes::from(utf8_bytes)))?
         },
+        (&Method::GET, "/request-target") => {
+            // A proxy gets the full uri, a server only the path
+            Response::builder()
+                .status(StatusCode::OK)
+                .body(full(req.uri().to_string()))?
+        },
+        (&Method::GET, "/gzip") => {
+            let accepts_gzip = req
+                .headers()
+                .get("accept-encoding")
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.contains("gzip"));
+
+            if accepts_gzip {
+                // output of: gzip.compress(b"Hello gzip", mtime=0) in python
+                let gzip_bytes: Vec<u8> = vec![31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 243, 72, 205, 201, 201, 87, 72, 175, 202, 44, 0, 0, 201, 105, 143, 144, 10, 0, 0, 0];
+
+                Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Encoding", "gzip")
+                    .body(full(Bytes::from(gzip_bytes)))?
+            } else {
+                Response::builder()
+                    .status(StatusCode::OK)
+                    .body(full("Hello gzip"))?
+            }
+        },
+        (&Method::GET, "/redirect") => {
+            Response::builder()
+                .status(StatusCode::FOUND)
+                .header("Location", "/utf8test")
+                .body(full(Bytes::new()))?
+        },
+        (&Method::POST, "/redirect-307") => {
+            // The client has to send the same body to the new location
+            Response::builder()
+                .status(StatusCode::TEMPORARY_REDIRECT)
+                .header("Location", "echo")
+                .body(full(Bytes::new()))?
+        },
+        (&Method::POST, "/echo") => {
+            // Send the body back, along with how it was sent
+            let transfer = match req.headers().get("content-length") {
+                Some(length) => format!("content-length {}", length.to_str()?),
+                None => "chunked".to_string(),
+            };
+            let body = req.into_body().collect().await?.to_bytes();
+
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("X-Received-With", transfer)
+                .body(full(body))?
+        },
         _ => {
             // Default response (original functionality)
             // output of: Encode.to_bytes({foo: "Hello Json!"}, Json.utf8)
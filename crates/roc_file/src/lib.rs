@@ -5,7 +5,7 @@ use roc_std_heap::ThreadSafeRefcountedResourceHeap;
 use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::fs::File;
-use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
 use std::path::Path;
 use std::sync::OnceLock;
 use std::{env, io};
@@ -25,6 +25,42 @@ pub fn heap() -> &'static ThreadSafeRefcountedResourceHeap<BufReader<File>> {
     })
 }
 
+pub fn walk_heap() -> &'static ThreadSafeRefcountedResourceHeap<DirWalker> {
+    static WALK_HEAP: OnceLock<ThreadSafeRefcountedResourceHeap<DirWalker>> = OnceLock::new();
+    WALK_HEAP.get_or_init(|| {
+        let default_max_walkers = 1024;
+        let max_walkers = env::var("ROC_BASIC_CLI_MAX_DIR_WALKERS")
+            .map(|v| v.parse().unwrap_or(default_max_walkers))
+            .unwrap_or(default_max_walkers);
+        ThreadSafeRefcountedResourceHeap::new(max_walkers)
+            .expect("Failed to allocate mmap for directory walker references.")
+    })
+}
+
+pub fn lock_heap() -> &'static ThreadSafeRefcountedResourceHeap<FileLock> {
+    static LOCK_HEAP: OnceLock<ThreadSafeRefcountedResourceHeap<FileLock>> = OnceLock::new();
+    LOCK_HEAP.get_or_init(|| {
+        let default_max_locks = 65536;
+        let max_locks = env::var("ROC_BASIC_CLI_MAX_FILE_LOCKS")
+            .map(|v| v.parse().unwrap_or(default_max_locks))
+            .unwrap_or(default_max_locks);
+        ThreadSafeRefcountedResourceHeap::new(max_locks)
+            .expect("Failed to allocate mmap for file lock references.")
+    })
+}
+
+pub fn mmap_heap() -> &'static ThreadSafeRefcountedResourceHeap<MappedFile> {
+    static MMAP_HEAP: OnceLock<ThreadSafeRefcountedResourceHeap<MappedFile>> = OnceLock::new();
+    MMAP_HEAP.get_or_init(|| {
+        let default_max_maps = 65536;
+        let max_maps = env::var("ROC_BASIC_CLI_MAX_MAPPED_FILES")
+            .map(|v| v.parse().unwrap_or(default_max_maps))
+            .unwrap_or(default_max_maps);
+        ThreadSafeRefcountedResourceHeap::new(max_maps)
+            .expect("Failed to allocate mmap for mapped file references.")
+    })
+}
+
 pub fn file_write_utf8(roc_path: &RocList<u8>, roc_str: &RocStr) -> RocResult<(), IOErr> {
     write_slice(roc_path, roc_str.as_str().as_bytes())
 }
@@ -43,6 +79,145 @@ fn write_slice(roc_path: &RocList<u8>, bytes: &[u8]) -> RocResult<(), IOErr> {
     }
 }
 
+/// Replaces the contents of a file without ever leaving it half-written.
+///
+/// The bytes are written to a temporary file in the same directory, which is
+/// fsynced and then renamed over the target. On unix the directory is fsynced
+/// afterwards so the rename itself survives a crash. A symbolic link is
+/// followed, the file it points to is replaced and the link is kept.
+pub fn file_write_atomic(roc_path: &RocList<u8>, roc_bytes: &RocList<u8>) -> RocResult<(), IOErr> {
+    match write_atomic(&path_from_roc_path(roc_path), roc_bytes.as_slice()) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(bytes))
+}
+
+/// Like [file_write_atomic], but the contents are written by `write`, which can fail with its own error.
+/// Nothing is replaced when `write` fails, the temporary file is removed.
+pub fn write_atomic_with<T, E: From<io::Error>>(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> Result<T, E>,
+) -> Result<T, E> {
+    let path = &resolve_sym_links(path)?;
+    let dir = parent_dir(path);
+    let (mut tmp_file, tmp_path) = create_sibling_temp_file(path)?;
+
+    let result = (|| -> Result<T, E> {
+        // Keep the permissions of the file we are replacing, if there is one.
+        if let Ok(metadata) = std::fs::metadata(path) {
+            tmp_file.set_permissions(metadata.permissions())?;
+        }
+
+        let written = write(&mut tmp_file)?;
+        tmp_file.sync_all()?;
+        drop(tmp_file);
+
+        std::fs::rename(&tmp_path, path)?;
+        sync_dir(dir)?;
+
+        Ok(written)
+    })();
+
+    if result.is_err() {
+        _ = std::fs::remove_file(&tmp_path);
+    }
+
+    result
+}
+
+/// Follows `path` while it is a symbolic link, so a rename replaces the file the link points to instead of the link.
+/// The target does not have to exist, writing through a dangling link creates it like `File::create` would.
+fn resolve_sym_links(path: &Path) -> io::Result<std::path::PathBuf> {
+    let mut path = path.to_path_buf();
+
+    // The same limit linux uses for ELOOP
+    for _ in 0..40 {
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                // An absolute target replaces the parent when joined
+                path = parent_dir(&path).join(std::fs::read_link(&path)?);
+            }
+            _ => return Ok(path),
+        }
+    }
+
+    Err(io::Error::new(
+        ErrorKind::Other,
+        "Too many levels of symbolic links",
+    ))
+}
+
+/// The directory that contains `path`, `.` for bare file names.
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Creates a new file next to `path` with a name that is not in use yet.
+fn create_sibling_temp_file(path: &Path) -> io::Result<(File, std::path::PathBuf)> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = path.file_name().unwrap_or(OsStr::new("file"));
+
+    loop {
+        let suffix = format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(truncated_file_name(file_name, MAX_FILE_NAME_LEN - 1 - suffix.len()));
+        tmp_name.push(suffix);
+
+        let tmp_path = parent_dir(path).join(tmp_name);
+
+        match File::options().write(true).create_new(true).open(&tmp_path) {
+            Ok(file) => return Ok((file, tmp_path)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// The longest file name most filesystems allow, in bytes (NAME_MAX on linux).
+/// Windows counts UTF-16 code units instead, which are never more than the UTF-8 bytes.
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// Shortens `file_name` to at most `max_len` bytes, so the temporary file of a long file name can still be created.
+/// The name only has to be recognizable, a name that is not valid unicode is shortened lossily.
+fn truncated_file_name(file_name: &OsStr, max_len: usize) -> Cow<'_, OsStr> {
+    if file_name.len() <= max_len {
+        return Cow::Borrowed(file_name);
+    }
+
+    let lossy = file_name.to_string_lossy();
+    let mut end = max_len.min(lossy.len());
+    while !lossy.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Cow::Owned(std::ffi::OsString::from(&lossy[..end]))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(windows)]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    // Directories can not be opened as files on windows, the rename is already durable there.
+    Ok(())
+}
+
 #[repr(C)]
 pub struct InternalPathType {
     is_dir: bool,
@@ -62,6 +237,153 @@ pub fn path_type(roc_path: &RocList<u8>) -> RocResult<InternalPathType, IOErr> {
     }
 }
 
+/// The variants are sorted alphabetically to match the tag ids roc uses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum InternalFileType {
+    IsBlockDevice = 0,
+    IsCharDevice = 1,
+    IsDir = 2,
+    IsFifo = 3,
+    IsFile = 4,
+    IsOther = 5,
+    IsSocket = 6,
+    IsSymLink = 7,
+}
+
+impl From<std::fs::FileType> for InternalFileType {
+    fn from(file_type: std::fs::FileType) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            if file_type.is_block_device() {
+                return InternalFileType::IsBlockDevice;
+            } else if file_type.is_char_device() {
+                return InternalFileType::IsCharDevice;
+            } else if file_type.is_fifo() {
+                return InternalFileType::IsFifo;
+            } else if file_type.is_socket() {
+                return InternalFileType::IsSocket;
+            }
+        }
+
+        if file_type.is_symlink() {
+            InternalFileType::IsSymLink
+        } else if file_type.is_dir() {
+            InternalFileType::IsDir
+        } else if file_type.is_file() {
+            InternalFileType::IsFile
+        } else {
+            InternalFileType::IsOther
+        }
+    }
+}
+
+/// Times are in nanoseconds since the UNIX epoch, `created` is 0 when the
+/// platform does not record it.
+#[repr(C)]
+pub struct InternalMetadata {
+    pub accessed: roc_std::U128,
+    pub changed: roc_std::U128,
+    pub created: roc_std::U128,
+    pub modified: roc_std::U128,
+    pub device: u64,
+    pub inode: u64,
+    pub nlink: u64,
+    pub size: u64,
+    pub gid: u32,
+    pub mode: u32,
+    pub uid: u32,
+    pub file_type: InternalFileType,
+}
+
+pub fn path_metadata(
+    roc_path: &RocList<u8>,
+    follow_symlinks: bool,
+) -> RocResult<InternalMetadata, IOErr> {
+    let path = path_from_roc_path(roc_path);
+    let metadata_res = if follow_symlinks {
+        std::fs::metadata(path)
+    } else {
+        std::fs::symlink_metadata(path)
+    };
+
+    match metadata_res {
+        Ok(metadata) => RocResult::ok(metadata_to_internal(&metadata)),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+fn system_time_to_nanos(time: std::time::SystemTime) -> roc_std::U128 {
+    let nanos = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+
+    roc_std::U128::from(nanos)
+}
+
+/// Negative for times before the UNIX epoch.
+fn system_time_to_signed_nanos(time: std::time::SystemTime) -> i128 {
+    match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos() as i128,
+        Err(err) => -(err.duration().as_nanos() as i128),
+    }
+}
+
+#[cfg(unix)]
+fn metadata_to_internal(metadata: &std::fs::Metadata) -> InternalMetadata {
+    use std::os::unix::fs::MetadataExt;
+
+    let to_nanos = |secs: i64, nsecs: i64| -> roc_std::U128 {
+        roc_std::U128::from((secs.max(0) as u128) * 1_000_000_000 + nsecs.max(0) as u128)
+    };
+
+    InternalMetadata {
+        accessed: to_nanos(metadata.atime(), metadata.atime_nsec()),
+        changed: to_nanos(metadata.ctime(), metadata.ctime_nsec()),
+        created: metadata
+            .created()
+            .map(system_time_to_nanos)
+            .unwrap_or(roc_std::U128::from(0)),
+        modified: to_nanos(metadata.mtime(), metadata.mtime_nsec()),
+        device: metadata.dev(),
+        inode: metadata.ino(),
+        nlink: metadata.nlink(),
+        size: metadata.size(),
+        gid: metadata.gid(),
+        mode: metadata.mode(),
+        uid: metadata.uid(),
+        file_type: metadata.file_type().into(),
+    }
+}
+
+#[cfg(windows)]
+fn metadata_to_internal(metadata: &std::fs::Metadata) -> InternalMetadata {
+    let time_or_zero = |time: io::Result<std::time::SystemTime>| {
+        time.map(system_time_to_nanos)
+            .unwrap_or(roc_std::U128::from(0))
+    };
+
+    InternalMetadata {
+        accessed: time_or_zero(metadata.accessed()),
+        // Windows has no inode change time, the last write time is the closest thing.
+        changed: time_or_zero(metadata.modified()),
+        created: time_or_zero(metadata.created()),
+        modified: time_or_zero(metadata.modified()),
+        // Placeholders, these are documented in Path.Metadata.
+        device: 0,
+        inode: 0,
+        nlink: 1,
+        size: metadata.len(),
+        gid: 0,
+        mode: if metadata.permissions().readonly() { 0o444 } else { 0o666 },
+        uid: 0,
+        file_type: metadata.file_type().into(),
+    }
+}
+
 #[cfg(target_family = "unix")]
 pub fn path_from_roc_path(bytes: &RocList<u8>) -> Cow<'_, Path> {
     use std::os::unix::ffi::OsStrExt;
@@ -84,19 +406,141 @@ pub fn path_from_roc_path(bytes: &RocList<u8>) -> Cow<'_, Path> {
 }
 
 pub fn file_read_bytes(roc_path: &RocList<u8>) -> RocResult<RocList<u8>, IOErr> {
-    // TODO: write our own duplicate of `read_to_end` that directly fills a `RocList<u8>`.
-    // This adds an extra O(n) copy.
-    let mut bytes = Vec::new();
+    file_read_into(roc_path, RocList::empty())
+}
 
-    match File::open(path_from_roc_path(roc_path)) {
-        Ok(mut file) => match file.read_to_end(&mut bytes) {
-            Ok(_bytes_read) => RocResult::ok(RocList::from(bytes.as_slice())),
-            Err(err) => RocResult::err(err.into()),
+/// Replaces the contents of `buffer` with the contents of the file, reusing its capacity.
+/// The list is grown once to fit the whole file when its size is known, and the bytes are read straight into it.
+pub fn file_read_into(roc_path: &RocList<u8>, mut buffer: RocList<u8>) -> RocResult<RocList<u8>, IOErr> {
+    let mut file = match File::open(path_from_roc_path(roc_path)) {
+        Ok(file) => file,
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    if buffer.is_unique() {
+        // SAFETY: the list is unique and a length of 0 has no elements that need to be initialized
+        unsafe { buffer.set_len(0) };
+    } else {
+        buffer = RocList::with_capacity(buffer.capacity());
+    }
+
+    // The size can be unknown (0) for special files like the ones in /proc, those are read until EOF anyway
+    if let Ok(metadata) = file.metadata() {
+        buffer.reserve(metadata.len() as usize);
+    }
+
+    match read_to_end(&mut file, &mut buffer) {
+        Ok(_) => RocResult::ok(buffer),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// How much the list grows at a time when the reader returns more than was reserved.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Like [std::io::Read::read_to_end], but reads straight into the spare capacity of a `RocList<u8>`,
+/// so the bytes are not copied from a buffer in between. Returns the number of bytes read.
+pub fn read_to_end<R: io::Read + ?Sized>(r: &mut R, buf: &mut RocList<u8>) -> io::Result<usize> {
+    let start_len = buf.len();
+
+    // The spare capacity of a list that is shared must not be written to, reserving gives us our own copy
+    if !buf.is_unique() {
+        buf.reserve(0);
+    }
+
+    // How many bytes of the spare capacity, starting at `buf.len()`, are already zeroed.
+    // Like `std::io::BorrowedBuf`, this makes sure every byte is only zeroed once.
+    let mut initialized = 0;
+
+    loop {
+        if buf.len() == buf.capacity() {
+            // The list is often exactly as big as the file, check for EOF before growing it
+            let mut probe = [0u8; 32];
+            match r.read(&mut probe) {
+                Ok(0) => return Ok(buf.len() - start_len),
+                Ok(n) => buf.extend_from_slice(&probe[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+
+            buf.reserve(READ_CHUNK_SIZE);
+            initialized = 0;
+            continue;
+        }
+
+        let len = buf.len();
+        let spare_len = buf.capacity() - len;
+
+        // SAFETY: the list is unique and has room for `spare_len` more bytes after `len`.
+        // The bytes are zeroed first, `read` is allowed to look at the buffer it is given.
+        let spare = unsafe {
+            let spare_ptr = buf.as_mut_ptr().add(len);
+            std::ptr::write_bytes(spare_ptr.add(initialized), 0, spare_len - initialized);
+            std::slice::from_raw_parts_mut(spare_ptr, spare_len)
+        };
+        initialized = spare_len;
+
+        match r.read(spare) {
+            Ok(0) => return Ok(len - start_len),
+            Ok(n) => {
+                let n = n.min(spare_len);
+                // SAFETY: the spare capacity was zeroed, `read` filled the first `n` bytes of it
+                unsafe { buf.set_len(len + n) };
+                initialized -= n;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A read-only memory map of a file, `None` for an empty file because those can not be mapped.
+pub struct MappedFile {
+    mmap: Option<memmap2::Mmap>,
+}
+
+pub fn file_map_readonly(roc_path: &RocList<u8>) -> RocResult<RocBox<()>, IOErr> {
+    let file = match File::open(path_from_roc_path(roc_path)) {
+        Ok(file) => file,
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    let mmap = match file.metadata() {
+        Ok(metadata) if metadata.len() == 0 => None,
+        // SAFETY: the map is only read from. If another process truncates the file
+        // while it is mapped, reading the removed part crashes with SIGBUS, this is documented in File.roc.
+        Ok(_) => match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(mmap) => Some(mmap),
+            Err(err) => return RocResult::err(err.into()),
         },
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    match mmap_heap().alloc_for(MappedFile { mmap }) {
+        Ok(out) => RocResult::ok(out),
         Err(err) => RocResult::err(err.into()),
     }
 }
 
+pub fn file_mapped_len(data: RocBox<()>) -> u64 {
+    let mapped: &mut MappedFile = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    mapped.mmap.as_ref().map_or(0, |mmap| mmap.len() as u64)
+}
+
+/// Copies up to `len` bytes starting at `offset`, fewer bytes are returned near the end of the file.
+pub fn file_mapped_slice(data: RocBox<()>, offset: u64, len: u64) -> RocList<u8> {
+    let mapped: &mut MappedFile = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    let bytes: &[u8] = mapped.mmap.as_deref().unwrap_or(&[]);
+    let start = usize::try_from(offset).unwrap_or(usize::MAX).min(bytes.len());
+    let end = start
+        .saturating_add(usize::try_from(len).unwrap_or(usize::MAX))
+        .min(bytes.len());
+
+    RocList::from(&bytes[start..end])
+}
+
 pub fn file_reader(roc_path: &RocList<u8>, size: u64) -> RocResult<RocBox<()>, IOErr> {
     match File::open(path_from_roc_path(roc_path)) {
         Ok(file) => {
@@ -343,6 +787,178 @@ pub fn file_time_created(roc_path: &RocList<u8>) -> RocResult<roc_std::U128, IOE
     }
 }
 
+/// Note: on windows only the write bits are used, they toggle the readonly attribute.
+pub fn file_set_permissions(roc_path: &RocList<u8>, mode: u32) -> RocResult<(), IOErr> {
+    let rust_path = path_from_roc_path(roc_path);
+
+    #[cfg(unix)]
+    let permissions = std::fs::Permissions::from_mode(mode);
+
+    #[cfg(windows)]
+    let permissions = match std::fs::metadata(&rust_path) {
+        Ok(metadata) => {
+            let mut permissions = metadata.permissions();
+            permissions.set_readonly(mode & 0o222 == 0);
+            permissions
+        }
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    match std::fs::set_permissions(rust_path, permissions) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// Grows or shrinks the file to `len` bytes, a file that grows is filled with zeros.
+/// With `only_shrink`, a file that is already shorter than `len` is left as it is.
+pub fn file_set_len(roc_path: &RocList<u8>, len: u64, only_shrink: bool) -> RocResult<(), IOErr> {
+    let result = File::options()
+        .write(true)
+        .open(path_from_roc_path(roc_path))
+        .and_then(|file| {
+            if only_shrink && file.metadata()?.len() <= len {
+                Ok(())
+            } else {
+                file.set_len(len)
+            }
+        });
+
+    match result {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// Creates a named pipe, the mode is masked by the umask like it is for `mkfifo`.
+#[cfg(unix)]
+pub fn file_create_fifo(roc_path: &RocList<u8>, mode: u32) -> RocResult<(), IOErr> {
+    let c_path = match c_path(&path_from_roc_path(roc_path)) {
+        Ok(c_path) => c_path,
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    // SAFETY: the path is nul terminated
+    if unsafe { libc::mkfifo(c_path.as_ptr(), mode as libc::mode_t) } == 0 {
+        RocResult::ok(())
+    } else {
+        RocResult::err(io::Error::last_os_error().into())
+    }
+}
+
+#[cfg(windows)]
+pub fn file_create_fifo(_roc_path: &RocList<u8>, _mode: u32) -> RocResult<(), IOErr> {
+    RocResult::err(io::Error::new(ErrorKind::Unsupported, "Named pipes are not supported on Windows").into())
+}
+
+/// Changes the owner and group of a path.
+/// Like the `chown` syscall, `u32::MAX` (-1) leaves the uid or gid unchanged.
+pub fn file_chown(roc_path: &RocList<u8>, uid: u32, gid: u32) -> RocResult<(), IOErr> {
+    let rust_path = path_from_roc_path(roc_path);
+
+    #[cfg(unix)]
+    {
+        let uid = if uid == u32::MAX { None } else { Some(uid) };
+        let gid = if gid == u32::MAX { None } else { Some(gid) };
+
+        match std::os::unix::fs::chown(rust_path, uid, gid) {
+            Ok(()) => RocResult::ok(()),
+            Err(err) => RocResult::err(err.into()),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = (rust_path, uid, gid);
+        RocResult::err(IOErr{
+            msg: "Not yet implemented on windows.".into(),
+            tag: IOErrTag::Unsupported,
+        })
+    }
+}
+
+/// Times are in nanoseconds since the UNIX epoch, negative for times before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct InternalFileTimes {
+    pub accessed: roc_std::I128,
+    pub modified: roc_std::I128,
+}
+
+pub fn file_set_times(roc_path: &RocList<u8>, times: &InternalFileTimes) -> RocResult<(), IOErr> {
+    let rust_path = path_from_roc_path(roc_path);
+
+    match set_times(&rust_path, i128::from(times.accessed), i128::from(times.modified)) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+fn time_out_of_range() -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidInput,
+        "The time is out of the range this platform supports",
+    )
+}
+
+/// Sets the times by path, so the file does not have to be opened. Opening would block on a FIFO
+/// and fail for files the user may change the times of but not read.
+#[cfg(unix)]
+fn set_times(path: &Path, accessed_nanos: i128, modified_nanos: i128) -> io::Result<()> {
+    let to_timespec = |nanos: i128| -> io::Result<libc::timespec> {
+        // Some targets have padding fields in timespec, so it can not be built with a struct literal.
+        let mut timespec: libc::timespec = unsafe { std::mem::zeroed() };
+        timespec.tv_sec = libc::time_t::try_from(nanos.div_euclid(NANOS_PER_SEC))
+            .map_err(|_| time_out_of_range())?;
+        timespec.tv_nsec = nanos.rem_euclid(NANOS_PER_SEC) as _;
+        Ok(timespec)
+    };
+
+    let times = [to_timespec(accessed_nanos)?, to_timespec(modified_nanos)?];
+    let c_path = c_path(path)?;
+
+    if unsafe { libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(windows)]
+fn set_times(path: &Path, accessed_nanos: i128, modified_nanos: i128) -> io::Result<()> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+    // Needed to open directories
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+
+    let to_system_time = |nanos: i128| -> io::Result<std::time::SystemTime> {
+        let abs_nanos = nanos.unsigned_abs();
+        let secs = u64::try_from(abs_nanos / NANOS_PER_SEC as u128).map_err(|_| time_out_of_range())?;
+        let since_epoch = std::time::Duration::new(secs, (abs_nanos % NANOS_PER_SEC as u128) as u32);
+
+        if nanos >= 0 {
+            std::time::UNIX_EPOCH.checked_add(since_epoch)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(since_epoch)
+        }
+        .ok_or_else(time_out_of_range)
+    };
+
+    let file_times = std::fs::FileTimes::new()
+        .set_accessed(to_system_time(accessed_nanos)?)
+        .set_modified(to_system_time(modified_nanos)?);
+
+    // Only the right to change attributes is asked for, not to read or write the contents.
+    File::options()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?
+        .set_times(file_times)
+}
+
 pub fn file_exists(roc_path: &RocList<u8>) -> RocResult<bool, IOErr> {
     let path = path_from_roc_path(roc_path);
     match path.try_exists() {
@@ -422,18 +1038,1262 @@ pub fn hard_link(path_from: &RocList<u8>, path_to: &RocList<u8>) -> RocResult<()
     }
 }
 
-#[cfg(target_family = "unix")]
-pub fn os_str_to_roc_path(os_str: &OsStr) -> RocList<u8> {
-    use std::os::unix::ffi::OsStrExt;
+pub fn sym_link(path_original: &RocList<u8>, path_link: &RocList<u8>) -> RocResult<(), IOErr> {
+    match create_sym_link(&path_from_roc_path(path_original), &path_from_roc_path(path_link)) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
 
-    RocList::from(os_str.as_bytes())
+#[cfg(unix)]
+fn create_sym_link(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
 }
 
-#[cfg(target_family = "windows")]
-pub fn os_str_to_roc_path(os_str: &OsStr) -> RocList<u8> {
-    use std::os::windows::ffi::OsStrExt;
+#[cfg(windows)]
+fn create_sym_link(original: &Path, link: &Path) -> io::Result<()> {
+    // Windows needs to know up front if the link will point to a directory.
+    if parent_dir(link).join(original).is_dir() {
+        std::os::windows::fs::symlink_dir(original, link)
+    } else {
+        std::os::windows::fs::symlink_file(original, link)
+    }
+}
 
-    let bytes: Vec<_> = os_str.encode_wide().flat_map(|c| c.to_be_bytes()).collect();
+pub fn read_link(roc_path: &RocList<u8>) -> RocResult<RocList<u8>, IOErr> {
+    match std::fs::read_link(path_from_roc_path(roc_path)) {
+        Ok(path_buf) => RocResult::ok(os_str_to_roc_path(path_buf.as_os_str())),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
 
-    RocList::from(bytes.as_slice())
+pub fn canonicalize(roc_path: &RocList<u8>) -> RocResult<RocList<u8>, IOErr> {
+    match std::fs::canonicalize(path_from_roc_path(roc_path)) {
+        Ok(path_buf) => RocResult::ok(os_str_to_roc_path(path_buf.as_os_str())),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// What to do when the destination of a copy already exists.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Overwrite {
+    FailIfExists,
+    Overwrite,
+    SkipExisting,
+}
+
+impl From<u8> for Overwrite {
+    fn from(tag: u8) -> Self {
+        match tag {
+            0 => Overwrite::FailIfExists,
+            1 => Overwrite::Overwrite,
+            _ => Overwrite::SkipExisting,
+        }
+    }
+}
+
+/// What to do with symbolic links found while copying a directory.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SymLinks {
+    CopyLinks,
+    FollowLinks,
+    SkipLinks,
+}
+
+impl From<u8> for SymLinks {
+    fn from(tag: u8) -> Self {
+        match tag {
+            0 => SymLinks::CopyLinks,
+            1 => SymLinks::FollowLinks,
+            _ => SymLinks::SkipLinks,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CopyOptions {
+    overwrite: Overwrite,
+    preserve_mode: bool,
+    preserve_times: bool,
+}
+
+/// Returns the number of bytes copied, 0 if an existing destination was skipped.
+pub fn file_copy(
+    from_path: &RocList<u8>,
+    to_path: &RocList<u8>,
+    overwrite: u8,
+    preserve_mode: bool,
+    preserve_times: bool,
+) -> RocResult<u64, IOErr> {
+    let options = CopyOptions {
+        overwrite: overwrite.into(),
+        preserve_mode,
+        preserve_times,
+    };
+
+    match copy_file(&path_from_roc_path(from_path), &path_from_roc_path(to_path), options) {
+        Ok(bytes_copied) => RocResult::ok(bytes_copied),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+fn copy_file(from: &Path, to: &Path, options: CopyOptions) -> io::Result<u64> {
+    let mut from_file = File::open(from)?;
+    let from_metadata = from_file.metadata()?;
+
+    // Opening the destination would truncate the source before anything is read from it.
+    if is_same_file(from, &from_metadata, to)? {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} and {} are the same file", from.display(), to.display()),
+        ));
+    }
+
+    let mut open_options = File::options();
+    open_options.write(true);
+
+    match options.overwrite {
+        Overwrite::Overwrite => open_options.create(true).truncate(true),
+        Overwrite::FailIfExists | Overwrite::SkipExisting => open_options.create_new(true),
+    };
+
+    let mut to_file = match open_options.open(to) {
+        Ok(file) => file,
+        Err(err)
+            if err.kind() == ErrorKind::AlreadyExists
+                && options.overwrite == Overwrite::SkipExisting =>
+        {
+            return Ok(0)
+        }
+        Err(err) => return Err(err),
+    };
+
+    let bytes_copied = if try_reflink(&from_file, &to_file) {
+        from_metadata.len()
+    } else {
+        // On linux this uses copy_file_range or sendfile, so the data does not pass through our memory.
+        io::copy(&mut from_file, &mut to_file)?
+    };
+
+    copy_attributes(&to_file, &from_metadata, options)?;
+
+    Ok(bytes_copied)
+}
+
+/// Whether `to` exists and is the file at `from`, also through hard or symbolic links.
+#[cfg(unix)]
+fn is_same_file(_from: &Path, from_metadata: &std::fs::Metadata, to: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    match std::fs::metadata(to) {
+        Ok(to_metadata) => Ok(to_metadata.dev() == from_metadata.dev() && to_metadata.ino() == from_metadata.ino()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// The file index is not available on stable rust, hard links to the same file are not detected here.
+#[cfg(windows)]
+fn is_same_file(from: &Path, _from_metadata: &std::fs::Metadata, to: &Path) -> io::Result<bool> {
+    match std::fs::canonicalize(to) {
+        Ok(to) => Ok(std::fs::canonicalize(from)? == to),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Makes `to_file` share the data blocks of `from_file` on filesystems that support it (btrfs, xfs, ...).
+#[cfg(target_os = "linux")]
+fn try_reflink(from_file: &File, to_file: &File) -> bool {
+    use std::os::fd::AsRawFd;
+
+    // _IOW(0x94, 9, int) from linux/fs.h
+    const FICLONE: u32 = 0x4004_9409;
+
+    unsafe { libc::ioctl(to_file.as_raw_fd(), FICLONE as _, from_file.as_raw_fd()) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn try_reflink(_from_file: &File, _to_file: &File) -> bool {
+    false
+}
+
+fn copy_attributes(to_file: &File, from_metadata: &std::fs::Metadata, options: CopyOptions) -> io::Result<()> {
+    if options.preserve_mode {
+        to_file.set_permissions(from_metadata.permissions())?;
+    }
+
+    if options.preserve_times {
+        let times = std::fs::FileTimes::new()
+            .set_accessed(from_metadata.accessed()?)
+            .set_modified(from_metadata.modified()?);
+        to_file.set_times(times)?;
+    }
+
+    Ok(())
+}
+
+pub fn dir_copy_all(
+    from_path: &RocList<u8>,
+    to_path: &RocList<u8>,
+    overwrite: u8,
+    sym_links: u8,
+    preserve_mode: bool,
+    preserve_times: bool,
+) -> RocResult<(), IOErr> {
+    let options = CopyOptions {
+        overwrite: overwrite.into(),
+        preserve_mode,
+        preserve_times,
+    };
+
+    let from = path_from_roc_path(from_path);
+    let to = path_from_roc_path(to_path);
+
+    let result = check_not_copying_into_itself(&from, &to).and_then(|from_resolved| {
+        // The source counts as followed, so a link back to it is reported as a loop.
+        let mut followed_dirs = vec![from_resolved];
+        copy_dir_all(&from, &to, options, sym_links.into(), &mut followed_dirs)
+    });
+
+    match result {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// Copying a directory into itself would never end, every copied directory is copied again.
+/// Returns the resolved source directory.
+fn check_not_copying_into_itself(from: &Path, to: &Path) -> io::Result<std::path::PathBuf> {
+    let from_resolved = std::fs::canonicalize(from)?;
+
+    // The destination usually does not exist yet, its parent has to exist for create_dir to succeed.
+    let to_resolved = match std::fs::canonicalize(to) {
+        Ok(resolved) => Some(resolved),
+        Err(_) => match to.file_name() {
+            Some(name) => std::fs::canonicalize(parent_dir(to)).ok().map(|parent| parent.join(name)),
+            None => None,
+        },
+    };
+
+    match to_resolved {
+        Some(to_resolved) if to_resolved.starts_with(&from_resolved) => Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Can not copy {} into itself at {}", from.display(), to.display()),
+        )),
+        _ => Ok(from_resolved),
+    }
+}
+
+fn copy_dir_all(
+    from: &Path,
+    to: &Path,
+    options: CopyOptions,
+    sym_links: SymLinks,
+    // Directories we reached through a symbolic link, used to detect link loops.
+    followed_dirs: &mut Vec<std::path::PathBuf>,
+) -> io::Result<()> {
+    match std::fs::create_dir(to) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::AlreadyExists && to.is_dir() => {}
+        Err(err) => return Err(err),
+    }
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let entry_from = entry.path();
+        let entry_to = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            match sym_links {
+                SymLinks::SkipLinks => {}
+                SymLinks::CopyLinks => copy_sym_link(&entry_from, &entry_to, options.overwrite)?,
+                SymLinks::FollowLinks => {
+                    let target_type = std::fs::metadata(&entry_from)?.file_type();
+                    if target_type.is_dir() {
+                        let target = std::fs::canonicalize(&entry_from)?;
+                        if followed_dirs.contains(&target) {
+                            return Err(io::Error::new(
+                                ErrorKind::Other,
+                                format!("Symbolic link loop at {}", entry_from.display()),
+                            ));
+                        }
+
+                        followed_dirs.push(target);
+                        copy_dir_all(&entry_from, &entry_to, options, sym_links, followed_dirs)?;
+                        followed_dirs.pop();
+                    } else if target_type.is_file() {
+                        copy_file(&entry_from, &entry_to, options)?;
+                    }
+                }
+            }
+        } else if file_type.is_dir() {
+            copy_dir_all(&entry_from, &entry_to, options, sym_links, followed_dirs)?;
+        } else if file_type.is_file() {
+            copy_file(&entry_from, &entry_to, options)?;
+        }
+        // Named pipes, sockets and devices are left out, reading a pipe would wait for a writer forever
+    }
+
+    // Done last, otherwise creating the entries would change the times and a readonly mode would stop us.
+    // This goes by path, directories can not be opened as files on windows.
+    if options.preserve_times {
+        let from_metadata = std::fs::metadata(from)?;
+        set_times(
+            to,
+            system_time_to_signed_nanos(from_metadata.accessed()?),
+            system_time_to_signed_nanos(from_metadata.modified()?),
+        )?;
+    }
+
+    if options.preserve_mode {
+        std::fs::set_permissions(to, std::fs::metadata(from)?.permissions())?;
+    }
+
+    Ok(())
+}
+
+fn copy_sym_link(from: &Path, to: &Path, overwrite: Overwrite) -> io::Result<()> {
+    if to.symlink_metadata().is_ok() {
+        match overwrite {
+            Overwrite::FailIfExists => return Err(ErrorKind::AlreadyExists.into()),
+            Overwrite::SkipExisting => return Ok(()),
+            Overwrite::Overwrite => std::fs::remove_file(to)?,
+        }
+    }
+
+    create_sym_link(&std::fs::read_link(from)?, to)
+}
+
+/// How many entries [dir_walk_next] returns per call, at most.
+const WALK_BATCH_SIZE: usize = 1024;
+
+pub struct DirWalker {
+    walk: ignore::Walk,
+    /// An error we ran into after some entries were already collected for a batch,
+    /// it is returned on the next call so the entries before it are not lost.
+    pending_err: Option<io::Error>,
+}
+
+/// The fields are sorted by alignment and then alphabetically to match the layout of the roc record.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct InternalWalkEntry {
+    pub depth: u64,
+    pub path: RocList<u8>,
+    pub file_type: InternalFileType,
+}
+
+impl roc_std::RocRefcounted for InternalWalkEntry {
+    fn inc(&mut self) {
+        self.path.inc();
+    }
+    fn dec(&mut self) {
+        self.path.dec();
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+/// `max_depth` is `u64::MAX` for no limit, a depth of 1 means only the direct children of `roc_path`.
+pub fn dir_walk(
+    roc_path: &RocList<u8>,
+    max_depth: u64,
+    follow_sym_links: bool,
+    skip_hidden: bool,
+    respect_gitignore: bool,
+) -> RocResult<RocBox<()>, IOErr> {
+    let path = path_from_roc_path(roc_path);
+
+    match std::fs::metadata(&path) {
+        Ok(m) if m.is_dir() => {}
+        Ok(_) => {
+            return RocResult::err(IOErr {
+                msg: "Not a directory".into(),
+                tag: IOErrTag::NotADirectory,
+            })
+        }
+        Err(err) => return RocResult::err(err.into()),
+    }
+
+    let mut builder = ignore::WalkBuilder::new(path);
+    builder
+        .max_depth(if max_depth == u64::MAX {
+            None
+        } else {
+            Some(max_depth as usize)
+        })
+        .follow_links(follow_sym_links)
+        .hidden(skip_hidden)
+        .parents(respect_gitignore)
+        .ignore(respect_gitignore)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        // .gitignore files should also be used outside of a git repository
+        .require_git(false);
+
+    let walker = DirWalker {
+        walk: builder.build(),
+        pending_err: None,
+    };
+
+    match walk_heap().alloc_for(walker) {
+        Ok(out) => RocResult::ok(out),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+/// Returns the next batch of entries, an empty list means the walk is done.
+pub fn dir_walk_next(data: RocBox<()>) -> RocResult<RocList<InternalWalkEntry>, IOErr> {
+    let walker: &mut DirWalker = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    if let Some(err) = walker.pending_err.take() {
+        return RocResult::err(err.into());
+    }
+
+    let mut entries = Vec::with_capacity(WALK_BATCH_SIZE);
+
+    while entries.len() < WALK_BATCH_SIZE {
+        match walker.walk.next() {
+            None => break,
+            // The root itself is not part of the walk, like with dir_list
+            Some(Ok(entry)) if entry.depth() == 0 => {}
+            Some(Ok(entry)) => entries.push(InternalWalkEntry {
+                depth: entry.depth() as u64,
+                path: os_str_to_roc_path(entry.path().as_os_str()),
+                file_type: entry
+                    .file_type()
+                    .map_or(InternalFileType::IsOther, InternalFileType::from),
+            }),
+            Some(Err(err)) => {
+                let err = walk_err_to_io_err(err);
+                if entries.is_empty() {
+                    return RocResult::err(err.into());
+                }
+                walker.pending_err = Some(err);
+                break;
+            }
+        }
+    }
+
+    RocResult::ok(RocList::from_iter(entries))
+}
+
+fn walk_err_to_io_err(err: ignore::Error) -> io::Error {
+    let kind = err.io_error().map_or(ErrorKind::Other, io::Error::kind);
+    io::Error::new(kind, err.to_string())
+}
+
+/// The fields are sorted by alignment and then alphabetically to match the layout of the roc record.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct InternalGlobErr {
+    pub err: IOErr,
+    pub path: RocList<u8>,
+}
+
+impl roc_std::RocRefcounted for InternalGlobErr {
+    fn inc(&mut self) {
+        self.err.inc();
+        self.path.inc();
+    }
+    fn dec(&mut self) {
+        self.err.dec();
+        self.path.dec();
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct InternalGlobResult {
+    pub errors: RocList<InternalGlobErr>,
+    pub matches: RocList<RocList<u8>>,
+}
+
+/// Returns all paths matching `pattern` sorted by their bytes. Directories that could not be read
+/// are returned in `errors` instead of failing the whole glob. Symbolic links to directories are followed.
+pub fn path_glob(pattern: &RocStr) -> RocResult<InternalGlobResult, IOErr> {
+    let pattern = pattern.as_str();
+
+    let matcher = match globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .backslash_escape(cfg!(unix))
+        .build()
+    {
+        Ok(glob) => glob.compile_matcher(),
+        Err(err) => {
+            return RocResult::err(IOErr {
+                msg: format!("Invalid glob pattern: {}", err).as_str().into(),
+                tag: IOErrTag::Other,
+            })
+        }
+    };
+
+    // Only walk the part of the tree that can match, e.g. `src` for `src/**/*.roc`.
+    let (base, rest) = split_glob_base(pattern);
+    let walk_root = if base.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base.as_path()
+    };
+    let max_depth = if rest.iter().any(|component| *component == "**") {
+        None
+    } else {
+        Some(rest.len())
+    };
+
+    let mut matches = Vec::new();
+    let mut errors = Vec::new();
+
+    if walk_root.symlink_metadata().is_err() {
+        // Nothing can match below a directory that does not exist
+    } else if rest.is_empty() {
+        // The pattern has no wildcards at all
+        matches.push(base.clone());
+    } else {
+        // Link loops are reported as errors by the walk
+        let walk = ignore::WalkBuilder::new(walk_root)
+            .standard_filters(false)
+            .follow_links(true)
+            .max_depth(max_depth)
+            .build();
+
+        for result in walk {
+            match result {
+                Ok(entry) if entry.depth() == 0 => {}
+                Ok(entry) => {
+                    // Walking `.` gives `./a`, but the pattern `*` should match it as `a`
+                    let path = if base.as_os_str().is_empty() {
+                        entry.path().strip_prefix(".").unwrap_or(entry.path())
+                    } else {
+                        entry.path()
+                    };
+                    if matcher.is_match(path) {
+                        matches.push(path.to_path_buf());
+                    }
+                }
+                Err(err) => {
+                    let path = glob_err_path(&err)
+                        .map(|p| os_str_to_roc_path(p.as_os_str()))
+                        .unwrap_or_else(RocList::empty);
+                    errors.push(InternalGlobErr {
+                        err: walk_err_to_io_err(err).into(),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+
+    matches.sort();
+
+    RocResult::ok(InternalGlobResult {
+        errors: RocList::from_iter(errors),
+        matches: RocList::from_iter(
+            matches
+                .iter()
+                .map(|path| os_str_to_roc_path(path.as_os_str())),
+        ),
+    })
+}
+
+/// Splits a pattern into the leading components without glob syntax and the remaining components.
+fn split_glob_base(pattern: &str) -> (std::path::PathBuf, Vec<&str>) {
+    let mut base = std::path::PathBuf::new();
+    let mut rest = Vec::new();
+
+    if pattern.starts_with('/') {
+        base.push("/");
+    }
+
+    for component in pattern.split('/').filter(|c| !c.is_empty()) {
+        if rest.is_empty() && !component.contains(['*', '?', '[', '{', '\\']) {
+            base.push(component);
+        } else {
+            rest.push(component);
+        }
+    }
+
+    (base, rest)
+}
+
+fn glob_err_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path.as_path()),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            glob_err_path(err)
+        }
+        ignore::Error::Loop { child, .. } => Some(child.as_path()),
+        _ => None,
+    }
+}
+
+/// The fields are sorted by alignment and then alphabetically to match the layout of the roc record.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct InternalDiskUsage {
+    pub available: u64,
+    pub free: u64,
+    pub fs_type: RocStr,
+    pub total: u64,
+}
+
+/// `available` is the space that can be used without root privileges, `free` includes the blocks reserved for root.
+#[cfg(unix)]
+pub fn dir_disk_usage(roc_path: &RocList<u8>) -> RocResult<InternalDiskUsage, IOErr> {
+    let c_path = match c_path(&path_from_roc_path(roc_path)) {
+        Ok(c_path) => c_path,
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    // SAFETY: statvfs only writes to the struct we pass it
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return RocResult::err(io::Error::last_os_error().into());
+    }
+
+    // The block counts are u32 on macOS
+    #[allow(clippy::unnecessary_cast)]
+    let (block_size, available, free, total) = (
+        stat.f_frsize as u64,
+        stat.f_bavail as u64,
+        stat.f_bfree as u64,
+        stat.f_blocks as u64,
+    );
+
+    RocResult::ok(InternalDiskUsage {
+        available: available * block_size,
+        free: free * block_size,
+        fs_type: fs_type_name(&c_path).as_str().into(),
+        total: total * block_size,
+    })
+}
+
+#[cfg(windows)]
+pub fn dir_disk_usage(_roc_path: &RocList<u8>) -> RocResult<InternalDiskUsage, IOErr> {
+    RocResult::err(io::Error::new(ErrorKind::Unsupported, "disk_usage! is not supported on Windows yet").into())
+}
+
+/// statvfs has no filesystem type, so this uses statfs. Linux only gives a magic number, the common ones are named here.
+#[cfg(target_os = "linux")]
+fn fs_type_name(c_path: &std::ffi::CStr) -> String {
+    // SAFETY: statfs only writes to the struct we pass it
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return "unknown".to_string();
+    }
+
+    // The magic numbers are 32 bits, f_type is a signed long on some targets and must not be sign extended.
+    #[allow(clippy::unnecessary_cast)]
+    let name = match stat.f_type as u32 {
+        0xEF53 => "ext4",
+        0x9123_683E => "btrfs",
+        0x5846_5342 => "xfs",
+        0x2FC1_2FC1 => "zfs",
+        0xF2F5_2010 => "f2fs",
+        0x0102_1994 => "tmpfs",
+        0x794C_7630 => "overlay",
+        0x6969 => "nfs",
+        0xFF53_4D42 | 0xFE53_4D42 => "cifs",
+        0x6573_5546 => "fuse",
+        0x4D44 => "vfat",
+        0x5346_544E => "ntfs",
+        0x2011_BAB0 => "exfat",
+        0x9660 => "iso9660",
+        0x0000_9FA0 => "proc",
+        0x6265_6572 => "sysfs",
+        0x0102_1997 => "9p",
+        other => return format!("0x{:x}", other),
+    };
+
+    name.to_string()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn fs_type_name(c_path: &std::ffi::CStr) -> String {
+    // SAFETY: statfs only writes to the struct we pass it
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return "unknown".to_string();
+    }
+
+    // SAFETY: f_fstypename is a nul terminated string like "apfs"
+    unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct InternalDirSize {
+    pub allocated: u64,
+    pub apparent: u64,
+}
+
+/// Sums the sizes of a directory and everything inside it, like `du`. Symbolic links are not followed
+/// and files with several hard links are only counted once.
+pub fn dir_size_recursive(roc_path: &RocList<u8>) -> RocResult<InternalDirSize, IOErr> {
+    let mut size = InternalDirSize::default();
+    let mut seen_inodes = std::collections::HashSet::new();
+
+    match add_dir_size(&path_from_roc_path(roc_path), &mut size, &mut seen_inodes) {
+        Ok(()) => RocResult::ok(size),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+fn add_dir_size(
+    path: &Path,
+    size: &mut InternalDirSize,
+    seen_inodes: &mut std::collections::HashSet<(u64, u64)>,
+) -> io::Result<()> {
+    let metadata = path.symlink_metadata()?;
+
+    #[cfg(unix)]
+    let allocated = {
+        use std::os::unix::fs::MetadataExt;
+
+        if metadata.nlink() > 1 && !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+            return Ok(());
+        }
+        // st_blocks is always in 512 byte units
+        metadata.blocks() * 512
+    };
+    #[cfg(windows)]
+    let allocated = {
+        let _ = &seen_inodes;
+        metadata.len()
+    };
+
+    size.apparent += metadata.len();
+    size.allocated += allocated;
+
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            add_dir_size(&entry?.path(), size, seen_inodes)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// An advisory lock on a file, it is released when this is dropped.
+pub struct FileLock {
+    file: File,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock as well, this makes sure it happens right away
+        let _ = unlock(&self.file);
+    }
+}
+
+/// Locks the file at `roc_path`, creating it if it does not exist yet.
+/// If `blocking` is false and another lock is in the way, this fails with `WouldBlock`.
+pub fn file_lock(roc_path: &RocList<u8>, exclusive: bool, blocking: bool) -> RocResult<RocBox<()>, IOErr> {
+    let file = match File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path_from_roc_path(roc_path))
+    {
+        Ok(file) => file,
+        Err(err) => return RocResult::err(err.into()),
+    };
+
+    if let Err(err) = lock(&file, exclusive, blocking) {
+        return RocResult::err(err.into());
+    }
+
+    match lock_heap().alloc_for(FileLock { file }) {
+        Ok(out) => RocResult::ok(out),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+pub fn file_unlock(data: RocBox<()>) -> RocResult<(), IOErr> {
+    let file_lock: &mut FileLock = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    match unlock(&file_lock.file) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+#[cfg(unix)]
+fn lock(file: &File, exclusive: bool, blocking: bool) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+    if !blocking {
+        operation |= libc::LOCK_NB;
+    }
+
+    flock(file.as_raw_fd(), operation)
+}
+
+#[cfg(unix)]
+fn unlock(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    flock(file.as_raw_fd(), libc::LOCK_UN)
+}
+
+#[cfg(unix)]
+fn flock(fd: std::os::unix::io::RawFd, operation: libc::c_int) -> io::Result<()> {
+    loop {
+        // SAFETY: the fd belongs to a file that is open for the duration of this call
+        if unsafe { libc::flock(fd, operation) } == 0 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn lock(_file: &File, _exclusive: bool, _blocking: bool) -> io::Result<()> {
+    Err(io::Error::new(
+        ErrorKind::Unsupported,
+        "File locks are not supported on Windows yet",
+    ))
+}
+
+#[cfg(windows)]
+fn unlock(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+/// Reads the extended attribute `name` of the file at `roc_path`, following symbolic links.
+/// If the file has no such attribute, the error is `Ok`. `NotFound` can not be used for that, it means the file is missing.
+pub fn file_get_xattr(roc_path: &RocList<u8>, name: &RocStr) -> RocResult<RocList<u8>, RocResult<(), IOErr>> {
+    match get_xattr(&path_from_roc_path(roc_path), name.as_str()) {
+        Ok(value) => RocResult::ok(RocList::from_slice(&value)),
+        Err(err) if is_no_xattr(&err) => RocResult::err(RocResult::ok(())),
+        Err(err) => RocResult::err(RocResult::err(xattr_err_to_io_err(err))),
+    }
+}
+
+pub fn file_set_xattr(roc_path: &RocList<u8>, name: &RocStr, value: &RocList<u8>) -> RocResult<(), IOErr> {
+    match set_xattr(&path_from_roc_path(roc_path), name.as_str(), value.as_slice()) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(xattr_err_to_io_err(err)),
+    }
+}
+
+pub fn file_list_xattrs(roc_path: &RocList<u8>) -> RocResult<RocList<RocStr>, IOErr> {
+    match list_xattrs(&path_from_roc_path(roc_path)) {
+        Ok(names) => RocResult::ok(
+            names
+                .split(|&byte| byte == 0)
+                .filter(|name| !name.is_empty())
+                .map(|name| RocStr::from(String::from_utf8_lossy(name).as_ref()))
+                .collect(),
+        ),
+        Err(err) => RocResult::err(xattr_err_to_io_err(err)),
+    }
+}
+
+/// If the file has no attribute called `name`, the error is `Ok` like for [file_get_xattr].
+pub fn file_remove_xattr(roc_path: &RocList<u8>, name: &RocStr) -> RocResult<(), RocResult<(), IOErr>> {
+    match remove_xattr(&path_from_roc_path(roc_path), name.as_str()) {
+        Ok(()) => RocResult::ok(()),
+        Err(err) if is_no_xattr(&err) => RocResult::err(RocResult::ok(())),
+        Err(err) => RocResult::err(RocResult::err(xattr_err_to_io_err(err))),
+    }
+}
+
+/// Whether the error means that the file has no attribute with the given name.
+fn is_no_xattr(err: &io::Error) -> bool {
+    #[cfg(target_os = "linux")]
+    return err.raw_os_error() == Some(libc::ENODATA);
+    #[cfg(target_os = "macos")]
+    return err.raw_os_error() == Some(libc::ENOATTR);
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    return {
+        let _ = err;
+        false
+    };
+}
+
+/// Filesystems without extended attributes, like tmpfs on older kernels or FAT, report ENOTSUP,
+/// which std does not map to `ErrorKind::Unsupported`.
+fn xattr_err_to_io_err(err: io::Error) -> IOErr {
+    #[cfg(unix)]
+    let unsupported = matches!(err.raw_os_error(), Some(code) if code == libc::ENOTSUP || code == libc::EOPNOTSUPP);
+    #[cfg(windows)]
+    let unsupported = false;
+
+    if unsupported {
+        IOErr {
+            msg: RocStr::empty(),
+            tag: IOErrTag::Unsupported,
+        }
+    } else {
+        err.into()
+    }
+}
+
+#[cfg(unix)]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(|err| io::Error::new(ErrorKind::InvalidInput, err))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn c_xattr_name(name: &str) -> io::Result<std::ffi::CString> {
+    std::ffi::CString::new(name).map_err(|err| io::Error::new(ErrorKind::InvalidInput, err))
+}
+
+/// Turns the return value of the xattr calls into the length in bytes or the error in errno.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn xattr_len(result: libc::ssize_t) -> io::Result<usize> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+/// Asks for the size first and then reads the value, trying again if it grew in between.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn read_xattr_buffer(mut read: impl FnMut(*mut libc::c_void, usize) -> libc::ssize_t) -> io::Result<Vec<u8>> {
+    loop {
+        let size = xattr_len(read(std::ptr::null_mut(), 0))?;
+        let mut buffer = vec![0u8; size];
+
+        match xattr_len(read(buffer.as_mut_ptr().cast(), buffer.len())) {
+            Ok(len) => {
+                buffer.truncate(len);
+                return Ok(buffer);
+            }
+            Err(err) if err.raw_os_error() == Some(libc::ERANGE) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_xattr(path: &Path, name: &str) -> io::Result<Vec<u8>> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated and the buffer is `size` bytes long
+    read_xattr_buffer(|buffer, size| unsafe { libc::getxattr(c_path.as_ptr(), c_name.as_ptr(), buffer, size) })
+}
+
+#[cfg(target_os = "macos")]
+fn get_xattr(path: &Path, name: &str) -> io::Result<Vec<u8>> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated and the buffer is `size` bytes long
+    read_xattr_buffer(|buffer, size| unsafe { libc::getxattr(c_path.as_ptr(), c_name.as_ptr(), buffer, size, 0, 0) })
+}
+
+#[cfg(target_os = "linux")]
+fn set_xattr(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated and the value is `value.len()` bytes long
+    let result = unsafe { libc::setxattr(c_path.as_ptr(), c_name.as_ptr(), value.as_ptr().cast(), value.len(), 0) };
+    xattr_len(result as libc::ssize_t).map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn set_xattr(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated and the value is `value.len()` bytes long
+    let result = unsafe { libc::setxattr(c_path.as_ptr(), c_name.as_ptr(), value.as_ptr().cast(), value.len(), 0, 0) };
+    xattr_len(result as libc::ssize_t).map(|_| ())
+}
+
+/// Returns the names as one buffer, each name ends with a nul byte.
+#[cfg(target_os = "linux")]
+fn list_xattrs(path: &Path) -> io::Result<Vec<u8>> {
+    let c_path = c_path(path)?;
+    // SAFETY: the path is nul terminated and the buffer is `size` bytes long
+    read_xattr_buffer(|buffer, size| unsafe { libc::listxattr(c_path.as_ptr(), buffer.cast(), size) })
+}
+
+#[cfg(target_os = "macos")]
+fn list_xattrs(path: &Path) -> io::Result<Vec<u8>> {
+    let c_path = c_path(path)?;
+    // SAFETY: the path is nul terminated and the buffer is `size` bytes long
+    read_xattr_buffer(|buffer, size| unsafe { libc::listxattr(c_path.as_ptr(), buffer.cast(), size, 0) })
+}
+
+#[cfg(target_os = "linux")]
+fn remove_xattr(path: &Path, name: &str) -> io::Result<()> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated
+    let result = unsafe { libc::removexattr(c_path.as_ptr(), c_name.as_ptr()) };
+    xattr_len(result as libc::ssize_t).map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn remove_xattr(path: &Path, name: &str) -> io::Result<()> {
+    let (c_path, c_name) = (c_path(path)?, c_xattr_name(name)?);
+    // SAFETY: the strings are nul terminated
+    let result = unsafe { libc::removexattr(c_path.as_ptr(), c_name.as_ptr(), 0) };
+    xattr_len(result as libc::ssize_t).map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn xattrs_unsupported<T>() -> io::Result<T> {
+    Err(io::Error::new(
+        ErrorKind::Unsupported,
+        "Extended attributes are not supported on this platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn get_xattr(_path: &Path, _name: &str) -> io::Result<Vec<u8>> {
+    xattrs_unsupported()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn set_xattr(_path: &Path, _name: &str, _value: &[u8]) -> io::Result<()> {
+    xattrs_unsupported()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn list_xattrs(_path: &Path) -> io::Result<Vec<u8>> {
+    xattrs_unsupported()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn remove_xattr(_path: &Path, _name: &str) -> io::Result<()> {
+    xattrs_unsupported()
+}
+
+/// Temporary files and directories that are deleted by [temp_cleanup] when the program exits.
+static TEMP_ENTRIES: std::sync::Mutex<Vec<std::path::PathBuf>> = std::sync::Mutex::new(Vec::new());
+
+pub fn temp_file_create(prefix: &RocStr, suffix: &RocStr) -> RocResult<RocList<u8>, IOErr> {
+    create_temp(prefix.as_str(), suffix.as_str(), |path| {
+        let mut options = File::options();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(path).map(|_file| ())
+    })
+}
+
+pub fn temp_dir_create(prefix: &RocStr, suffix: &RocStr) -> RocResult<RocList<u8>, IOErr> {
+    create_temp(prefix.as_str(), suffix.as_str(), |path| {
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(path)
+    })
+}
+
+/// Creates a new entry in the temp dir with `create`, which must fail with `AlreadyExists`
+/// instead of reusing an existing entry, so two programs can never end up with the same one.
+fn create_temp(
+    prefix: &str,
+    suffix: &str,
+    create: impl Fn(&Path) -> io::Result<()>,
+) -> RocResult<RocList<u8>, IOErr> {
+    if prefix.contains(std::path::is_separator) || suffix.contains(std::path::is_separator) {
+        return RocResult::err(IOErr {
+            msg: "The prefix and suffix of a temporary file can not contain a path separator".into(),
+            tag: IOErrTag::Other,
+        });
+    }
+
+    let temp_dir = env::temp_dir();
+
+    loop {
+        let path = temp_dir.join(format!("{}{}{}", prefix, random_temp_name(), suffix));
+
+        match create(&path) {
+            Ok(()) => {
+                let roc_path = os_str_to_roc_path(path.as_os_str());
+                TEMP_ENTRIES.lock().unwrap_or_else(|e| e.into_inner()).push(path);
+                return RocResult::ok(roc_path);
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return RocResult::err(err.into()),
+        }
+    }
+}
+
+/// 16 hex characters that are hard to guess, the hasher keys are random for every process.
+fn random_temp_name() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+/// Stops tracking a temporary file or directory, so it is not deleted when the program exits.
+pub fn temp_keep(roc_path: &RocList<u8>) {
+    let path = path_from_roc_path(roc_path);
+    TEMP_ENTRIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|entry| entry.as_path() != path.as_ref());
+}
+
+/// Deletes all temporary files and directories that were not kept, errors are ignored
+/// because the program is exiting and the OS will clean the temp dir eventually.
+pub fn temp_cleanup() {
+    let entries = std::mem::take(&mut *TEMP_ENTRIES.lock().unwrap_or_else(|e| e.into_inner()));
+
+    // Newest first, so a temp file inside a temp dir is deleted before the dir
+    for path in entries.iter().rev() {
+        match path.symlink_metadata() {
+            Ok(metadata) if metadata.is_dir() => {
+                let _ = std::fs::remove_dir_all(path);
+            }
+            Ok(_) => {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(target_family = "unix")]
+pub fn os_str_to_roc_path(os_str: &OsStr) -> RocList<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    RocList::from(os_str.as_bytes())
+}
+
+#[cfg(target_family = "windows")]
+pub fn os_str_to_roc_path(os_str: &OsStr) -> RocList<u8> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let bytes: Vec<_> = os_str.encode_wide().flat_map(|c| c.to_be_bytes()).collect();
+
+    RocList::from(bytes.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir, removed again when dropped.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("roc_file_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn roc_path(&self, name: &str) -> RocList<u8> {
+            os_str_to_roc_path(self.0.join(name).as_os_str())
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_all_skips_fifos() {
+        let dir = TempDir::new("copy_all_fifo");
+        std::fs::create_dir_all(dir.0.join("from/sub")).unwrap();
+        std::fs::write(dir.0.join("from/sub/file.txt"), b"data").unwrap();
+        Result::from(file_create_fifo(&dir.roc_path("from/sub/pipe"), 0o600)).unwrap();
+        std::os::unix::fs::symlink("pipe", dir.0.join("from/sub/pipe_link")).unwrap();
+
+        for sym_links in [SymLinks::SkipLinks, SymLinks::FollowLinks] {
+            let to = format!("to_{}", sym_links as u8);
+            let result = dir_copy_all(
+                &dir.roc_path("from"),
+                &dir.roc_path(&to),
+                Overwrite::FailIfExists as u8,
+                sym_links as u8,
+                false,
+                false,
+            );
+            Result::from(result).unwrap();
+
+            let copied = dir.0.join(to).join("sub");
+            assert_eq!(std::fs::read(copied.join("file.txt")).unwrap(), b"data");
+            assert!(!copied.join("pipe").exists());
+            assert!(!copied.join("pipe_link").exists());
+        }
+    }
+
+    #[test]
+    fn write_atomic_with_the_longest_file_name() {
+        let dir = TempDir::new("write_atomic_long_name");
+        let name = "é".repeat(MAX_FILE_NAME_LEN / 2) + "x";
+        assert_eq!(name.len(), MAX_FILE_NAME_LEN);
+
+        let path = dir.0.join(&name);
+        write_atomic_with(&path, |file| file.write_all(b"data")).unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"data");
+        // The temporary file was renamed, nothing else is left behind
+        assert_eq!(std::fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn read_into_reuses_the_list() {
+        let dir = TempDir::new("read_into");
+        std::fs::write(dir.0.join("first"), b"hello world").unwrap();
+        std::fs::write(dir.0.join("second"), b"bye").unwrap();
+
+        let first = file_read_into(&dir.roc_path("first"), RocList::with_capacity(64));
+        let first = Result::from(first).unwrap();
+        assert_eq!(first.as_slice(), b"hello world");
+        let (ptr, capacity) = (first.as_ptr(), first.capacity());
+
+        let second = Result::from(file_read_into(&dir.roc_path("second"), first)).unwrap();
+        assert_eq!(second.as_slice(), b"bye");
+        assert_eq!(second.as_ptr(), ptr);
+        assert_eq!(second.capacity(), capacity);
+    }
+
+    /// Hands out at most `chunk` bytes per read, like a socket.
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl io::Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.chunk).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn read_to_end_in_small_chunks() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let mut reader = Chunked { data: &data, chunk: 1000 };
+        let mut buf = RocList::from_slice(b"head");
+
+        let read = read_to_end(&mut reader, &mut buf).unwrap();
+
+        assert_eq!(read, data.len());
+        assert_eq!(&buf[..4], b"head");
+        assert_eq!(&buf[4..], data.as_slice());
+    }
 }
@@ -6,19 +6,51 @@
 use core::ffi::c_void;
 use bytes::Bytes;
 use http_body_util::BodyExt;
-use hyper_util::rt::TokioExecutor;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioTimer};
 use roc_env::arg::ArgToAndFromHost;
 use roc_io_error::IOErr;
 use roc_std::{RocBox, RocList, RocResult, RocStr};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Runtime;
 
+/// Request bodies are either the bytes from roc or streamed from a file.
+type HttpBody = http_body_util::combinators::BoxBody<Bytes, Box<dyn std::error::Error + Send + Sync>>;
+
+type HttpClient = Client<hyper_rustls::HttpsConnector<roc_http::proxy::ProxyConnector>, HttpBody>;
+
 thread_local! {
    static TOKIO_RUNTIME: Runtime = tokio::runtime::Builder::new_current_thread()
        .enable_io()
        .enable_time()
        .build()
        .unwrap();
+
+   // Created on the first request, so the native root certificates are only loaded once
+   // and connections are kept open to be reused by the next requests.
+   static HTTP_CLIENTS: RefCell<roc_http::proxy::ClientCache<ProxiedClient>> = RefCell::new(roc_http::proxy::ClientCache::new());
+
+   static HTTP_POOL_OPTIONS: Cell<HttpPoolOptions> = const { Cell::new(HttpPoolOptions::DEFAULT) };
+
+   // Set by roc_fx_http_configure_tls, until then only the native root certificates are trusted.
+   static HTTP_TLS_CONFIG: RefCell<Option<rustls::ClientConfig>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Copy)]
+struct HttpPoolOptions {
+    idle_timeout: Option<Duration>,
+    max_idle_per_host: usize,
+}
+
+impl HttpPoolOptions {
+    // The same as the defaults of hyper's client
+    const DEFAULT: HttpPoolOptions = HttpPoolOptions {
+        idle_timeout: Some(Duration::from_secs(90)),
+        max_idle_per_host: usize::MAX,
+    };
 }
 
 /// # Safety
@@ -52,11 +84,36 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
         heap.dealloc(c_ptr);
         return;
     }
+    let heap = roc_file::mmap_heap();
+    if heap.in_range(c_ptr) {
+        heap.dealloc(c_ptr);
+        return;
+    }
+    let heap = roc_file::lock_heap();
+    if heap.in_range(c_ptr) {
+        heap.dealloc(c_ptr);
+        return;
+    }
+    let heap = roc_file::walk_heap();
+    if heap.in_range(c_ptr) {
+        heap.dealloc(c_ptr);
+        return;
+    }
+    let heap = roc_watcher::heap();
+    if heap.in_range(c_ptr) {
+        heap.dealloc(c_ptr);
+        return;
+    }
     let heap = roc_http::heap();
     if heap.in_range(c_ptr) {
         heap.dealloc(c_ptr);
         return;
     }
+    let heap = roc_http::response_stream_heap();
+    if heap.in_range(c_ptr) {
+        heap.dealloc(c_ptr);
+        return;
+    }
     // !! If you make any changes to this function, you may also need to update roc_dealloc in
     // https://github.com/roc-lang/basic-webserver
     let heap = roc_sqlite::heap();
@@ -73,6 +130,7 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 #[no_mangle]
 pub unsafe extern "C" fn roc_panic(msg: &RocStr, tag_id: u32) {
     _ = crossterm::terminal::disable_raw_mode();
+    roc_file::temp_cleanup();
     match tag_id {
         0 => {
             eprintln!("Roc crashed with:\n\n\t{}\n", msg.as_str());
@@ -142,6 +200,7 @@ pub unsafe extern "C" fn roc_expect_failed(
         eprintln!();
     }
 
+    roc_file::temp_cleanup();
     std::process::exit(1);
 }
 
@@ -314,10 +373,22 @@ pub fn init() {
         roc_fx_tty_mode_raw as _,
         roc_fx_file_write_utf8 as _,
         roc_fx_file_write_bytes as _,
+        roc_fx_file_write_atomic as _,
         roc_fx_path_type as _,
+        roc_fx_path_metadata as _,
         roc_fx_file_read_bytes as _,
+        roc_fx_file_read_into as _,
         roc_fx_file_reader as _,
         roc_fx_file_read_line as _,
+        roc_fx_file_map_readonly as _,
+        roc_fx_file_mapped_len as _,
+        roc_fx_file_mapped_slice as _,
+        roc_fx_file_lock as _,
+        roc_fx_file_unlock as _,
+        roc_fx_file_get_xattr as _,
+        roc_fx_file_set_xattr as _,
+        roc_fx_file_list_xattrs as _,
+        roc_fx_file_remove_xattr as _,
         roc_fx_file_delete as _,
         roc_fx_file_size_in_bytes as _,
         roc_fx_file_is_executable as _,
@@ -326,14 +397,35 @@ pub fn init() {
         roc_fx_file_time_accessed as _,
         roc_fx_file_time_modified as _,
         roc_fx_file_time_created as _,
+        roc_fx_file_set_permissions as _,
+        roc_fx_file_set_len as _,
+        roc_fx_file_create_fifo as _,
+        roc_fx_file_chown as _,
+        roc_fx_file_set_times as _,
         roc_fx_file_exists as _,
         roc_fx_file_rename as _,
+        roc_fx_file_copy as _,
         roc_fx_hard_link as _,
+        roc_fx_sym_link as _,
+        roc_fx_read_link as _,
+        roc_fx_canonicalize as _,
+        roc_fx_path_glob as _,
         roc_fx_cwd as _,
         roc_fx_posix_time as _,
         roc_fx_sleep_millis as _,
         roc_fx_dir_list as _,
+        roc_fx_dir_walk as _,
+        roc_fx_dir_walk_next as _,
+        roc_fx_dir_disk_usage as _,
+        roc_fx_dir_size_recursive as _,
         roc_fx_send_request as _,
+        roc_fx_http_send_all as _,
+        roc_fx_http_configure_pool as _,
+        roc_fx_http_configure_tls as _,
+        roc_fx_http_send_streaming as _,
+        roc_fx_http_send_file as _,
+        roc_fx_http_stream_next_chunk as _,
+        roc_fx_http_stream_to_file as _,
         roc_fx_tcp_connect as _,
         roc_fx_tcp_read_up_to as _,
         roc_fx_tcp_read_exactly as _,
@@ -345,12 +437,20 @@ pub fn init() {
         roc_fx_dir_create_all as _,
         roc_fx_dir_delete_empty as _,
         roc_fx_dir_delete_all as _,
+        roc_fx_dir_copy_all as _,
         roc_fx_current_arch_os as _,
         roc_fx_temp_dir as _,
+        roc_fx_temp_file_create as _,
+        roc_fx_temp_dir_create as _,
+        roc_fx_temp_keep as _,
         roc_fx_get_locale as _,
         roc_fx_get_locales as _,
         roc_fx_random_u64 as _,
         roc_fx_random_u32 as _,
+        roc_fx_watcher_new as _,
+        roc_fx_watcher_watch as _,
+        roc_fx_watcher_unwatch as _,
+        roc_fx_watcher_next as _,
         roc_fx_sqlite_bind as _,
         roc_fx_sqlite_column_value as _,
         roc_fx_sqlite_columns as _,
@@ -397,6 +497,8 @@ pub extern "C" fn rust_main(args: RocList<ArgToAndFromHost>) -> i32 {
         exit_code
     };
 
+    roc_file::temp_cleanup();
+
     exit_code
 }
 
@@ -491,6 +593,14 @@ pub extern "C" fn roc_fx_file_write_bytes(
     roc_file::file_write_bytes(roc_path, roc_bytes)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_file_write_atomic(
+    roc_path: &RocList<u8>,
+    roc_bytes: &RocList<u8>,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_write_atomic(roc_path, roc_bytes)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_path_type(
     roc_path: &RocList<u8>,
@@ -498,6 +608,14 @@ pub extern "C" fn roc_fx_path_type(
     roc_file::path_type(roc_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_path_metadata(
+    roc_path: &RocList<u8>,
+    follow_symlinks: bool,
+) -> RocResult<roc_file::InternalMetadata, roc_io_error::IOErr> {
+    roc_file::path_metadata(roc_path, follow_symlinks)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_file_read_bytes(
     roc_path: &RocList<u8>,
@@ -505,6 +623,16 @@ pub extern "C" fn roc_fx_file_read_bytes(
     roc_file::file_read_bytes(roc_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_file_read_into(
+    roc_path: &RocList<u8>,
+    roc_buffer: RocList<u8>,
+) -> RocResult<RocList<u8>, roc_io_error::IOErr> {
+    // The list is taken by value so roc hands over its reference, which lets the bytes be read
+    // into its existing allocation when nothing else holds on to it.
+    roc_file::file_read_into(roc_path, roc_buffer)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_file_reader(
     roc_path: &RocList<u8>,
@@ -520,6 +648,69 @@ pub extern "C" fn roc_fx_file_read_line(
     roc_file::file_read_line(data)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_file_map_readonly(
+    roc_path: &RocList<u8>,
+) -> RocResult<RocBox<()>, roc_io_error::IOErr> {
+    roc_file::file_map_readonly(roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_mapped_len(data: RocBox<()>) -> u64 {
+    roc_file::file_mapped_len(data)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_mapped_slice(data: RocBox<()>, offset: u64, len: u64) -> RocList<u8> {
+    roc_file::file_mapped_slice(data, offset, len)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_lock(
+    roc_path: &RocList<u8>,
+    exclusive: bool,
+    blocking: bool,
+) -> RocResult<RocBox<()>, roc_io_error::IOErr> {
+    roc_file::file_lock(roc_path, exclusive, blocking)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_unlock(data: RocBox<()>) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_unlock(data)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_get_xattr(
+    roc_path: &RocList<u8>,
+    name: &RocStr,
+) -> RocResult<RocList<u8>, RocResult<(), roc_io_error::IOErr>> {
+    roc_file::file_get_xattr(roc_path, name)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_set_xattr(
+    roc_path: &RocList<u8>,
+    name: &RocStr,
+    value: &RocList<u8>,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_set_xattr(roc_path, name, value)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_list_xattrs(
+    roc_path: &RocList<u8>,
+) -> RocResult<RocList<RocStr>, roc_io_error::IOErr> {
+    roc_file::file_list_xattrs(roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_remove_xattr(
+    roc_path: &RocList<u8>,
+    name: &RocStr,
+) -> RocResult<(), RocResult<(), roc_io_error::IOErr>> {
+    roc_file::file_remove_xattr(roc_path, name)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_file_delete(roc_path: &RocList<u8>) -> RocResult<(), roc_io_error::IOErr> {
     roc_file::file_delete(roc_path)
@@ -574,6 +765,48 @@ pub extern "C" fn roc_fx_file_time_created(
     roc_file::file_time_created(roc_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_file_set_permissions(
+    roc_path: &RocList<u8>,
+    mode: u32,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_set_permissions(roc_path, mode)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_set_len(
+    roc_path: &RocList<u8>,
+    len: u64,
+    only_shrink: bool,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_set_len(roc_path, len, only_shrink)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_create_fifo(
+    roc_path: &RocList<u8>,
+    mode: u32,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_create_fifo(roc_path, mode)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_chown(
+    roc_path: &RocList<u8>,
+    uid: u32,
+    gid: u32,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_chown(roc_path, uid, gid)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_file_set_times(
+    roc_path: &RocList<u8>,
+    times: &roc_file::InternalFileTimes,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::file_set_times(roc_path, times)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_file_exists(roc_path: &RocList<u8>) -> RocResult<bool, roc_io_error::IOErr> {
     roc_file::file_exists(roc_path)
@@ -587,6 +820,17 @@ pub extern "C" fn roc_fx_file_rename(
     roc_file::file_rename(from_path, to_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_file_copy(
+    from_path: &RocList<u8>,
+    to_path: &RocList<u8>,
+    overwrite: u8,
+    preserve_mode: bool,
+    preserve_times: bool,
+) -> RocResult<u64, roc_io_error::IOErr> {
+    roc_file::file_copy(from_path, to_path, overwrite, preserve_mode, preserve_times)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_cwd() -> RocResult<RocList<u8>, ()> {
     roc_env::cwd()
@@ -609,104 +853,581 @@ pub extern "C" fn roc_fx_dir_list(
     roc_file::dir_list(roc_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_dir_walk(
+    roc_path: &RocList<u8>,
+    max_depth: u64,
+    follow_sym_links: bool,
+    skip_hidden: bool,
+    respect_gitignore: bool,
+) -> RocResult<RocBox<()>, roc_io_error::IOErr> {
+    roc_file::dir_walk(
+        roc_path,
+        max_depth,
+        follow_sym_links,
+        skip_hidden,
+        respect_gitignore,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_dir_walk_next(
+    data: RocBox<()>,
+) -> RocResult<RocList<roc_file::InternalWalkEntry>, roc_io_error::IOErr> {
+    roc_file::dir_walk_next(data)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_dir_disk_usage(
+    roc_path: &RocList<u8>,
+) -> RocResult<roc_file::InternalDiskUsage, roc_io_error::IOErr> {
+    roc_file::dir_disk_usage(roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_dir_size_recursive(
+    roc_path: &RocList<u8>,
+) -> RocResult<roc_file::InternalDirSize, roc_io_error::IOErr> {
+    roc_file::dir_size_recursive(roc_path)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_send_request(
     roc_request: &roc_http::RequestToAndFromHost,
-) -> roc_http::ResponseToAndFromHost {
+) -> RocResult<roc_http::ResponseToAndFromHost, roc_http::HttpErr> {
     TOKIO_RUNTIME.with(|rt| {
-        let request = match roc_request.to_hyper_request() {
-            Ok(r) => r,
-            Err(err) => return err.into(),
+        let (request, body) = match to_client_request(roc_request) {
+            Ok(request_and_body) => request_and_body,
+            Err(err) => return RocResult::err(err),
         };
 
-        match roc_request.has_timeout() {
-            Some(time_limit) => rt
-                .block_on(async {
-                    tokio::time::timeout(
-                        Duration::from_millis(time_limit),
-                        async_send_request(request),
-                    )
-                    .await
-                })
-                .unwrap_or_else(|_err| roc_http::ResponseToAndFromHost {
-                    status: 408,
-                    headers: RocList::empty(),
-                    body: roc_http::REQUEST_TIMEOUT_BODY.into(),
-                }),
-            None => rt.block_on(async_send_request(request)),
+        let timeout = roc_request.has_timeout().map(Duration::from_millis);
+        let options = SendOptions::of(roc_request);
+        let sent = async_send_request(request, Some(body), &options);
+
+        match rt.block_on(with_timeout(timeout, sent)) {
+            Ok(response) => RocResult::ok(response),
+            Err(err) => RocResult::err(err),
         }
     })
 }
 
-async fn async_send_request(request: hyper::Request<http_body_util::Full<Bytes>>) -> roc_http::ResponseToAndFromHost {
-    use hyper_util::client::legacy::Client;
-    use hyper_rustls::HttpsConnectorBuilder;
+/// Sends the requests concurrently, at most `max_concurrency` at a time, and returns the results in the same order.
+/// Each request has its own timeout, `timeout_ms` limits the whole batch: requests that did not finish in time fail with `Timeout`.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_send_all(
+    roc_requests: &RocList<roc_http::RequestToAndFromHost>,
+    max_concurrency: u64,
+    timeout_ms: u64,
+) -> RocList<RocResult<roc_http::ResponseToAndFromHost, roc_http::HttpErr>> {
+    let mut results: Vec<Option<Result<roc_http::ResponseToAndFromHost, roc_http::HttpErr>>> =
+        vec![None; roc_requests.len()];
+    let mut pending = Vec::new();
+
+    for (index, roc_request) in roc_requests.iter().enumerate() {
+        match to_client_request(roc_request) {
+            Ok((request, body)) => pending.push((
+                index,
+                request,
+                body,
+                roc_request.has_timeout().map(Duration::from_millis),
+                SendOptions::of(roc_request),
+            )),
+            Err(err) => results[index] = Some(Err(err)),
+        }
+    }
 
-    let https = match HttpsConnectorBuilder::new()
-        .with_native_roots()
-    {
-        Ok(builder) => builder
-            .https_or_http()
-            .enable_http1()
-            .build(),
-        Err(_) => {
-            return roc_http::ResponseToAndFromHost {
-                status: 500,
-                headers: RocList::empty(),
-                body: "Failed to initialize HTTPS connector with native roots".as_bytes().into(),
+    let max_concurrency = usize::try_from(max_concurrency).unwrap_or(usize::MAX).max(1);
+    let batch_timeout = if timeout_ms > 0 {
+        Some(Duration::from_millis(timeout_ms))
+    } else {
+        None
+    };
+
+    // The requests take turns on this thread, so the roc values in them never cross threads
+    let local = tokio::task::LocalSet::new();
+
+    TOKIO_RUNTIME.with(|rt| {
+        local.block_on(rt, async {
+            let mut pending = pending.into_iter();
+            let mut running = tokio::task::JoinSet::new();
+            // The index of each running request, a task that panicked only gives back its id
+            let mut task_indexes = HashMap::new();
+
+            let run_all = async {
+                loop {
+                    while running.len() < max_concurrency {
+                        let Some((index, request, body, timeout, options)) = pending.next() else {
+                            break;
+                        };
+
+                        let task = running.spawn_local(async move {
+                            let sent = async_send_request(request, Some(body), &options);
+                            (index, with_timeout(timeout, sent).await)
+                        });
+                        task_indexes.insert(task.id(), index);
+                    }
+
+                    match running.join_next().await {
+                        Some(Ok((index, result))) => results[index] = Some(result),
+                        // Only happens when sending a request panicked, tasks are not cancelled here.
+                        // Unwinding out of this extern "C" fn would abort, so the panic becomes the error of that request.
+                        Some(Err(join_err)) => {
+                            let index = task_indexes[&join_err.id()];
+                            results[index] = Some(Err(panicked_request_err(join_err)));
+                        }
+                        None => break,
+                    }
+                }
             };
+
+            match batch_timeout {
+                Some(time_limit) => {
+                    let _ = tokio::time::timeout(time_limit, run_all).await;
+                }
+                None => run_all.await,
+            }
+
+            // Stops the requests that are still running
+            running.shutdown().await;
+        })
+    });
+
+    RocList::from_iter(results.into_iter().map(|result| {
+        match result.unwrap_or_else(|| {
+            Err(roc_http::HttpErr::new(roc_http::HttpErrTag::Timeout, ""))
+        }) {
+            Ok(response) => RocResult::ok(response),
+            Err(err) => RocResult::err(err),
         }
+    }))
+}
+
+fn panicked_request_err(join_err: tokio::task::JoinError) -> roc_http::HttpErr {
+    let payload = join_err.into_panic();
+    let msg = match payload.downcast_ref::<&str>() {
+        Some(msg) => msg.to_string(),
+        None => payload.downcast_ref::<String>().cloned().unwrap_or_default(),
     };
 
-    let client: Client<_, http_body_util::Full<Bytes>> =
-        Client::builder(TokioExecutor::new()).build(https);
+    roc_http::HttpErr::new(
+        roc_http::HttpErrTag::Other,
+        &format!("Sending the request panicked: {msg}"),
+    )
+}
 
-    let response_res = client.request(request).await;
+/// Sends the request with the rest of the file `reader` reads from as the body, instead of the body of `roc_request`.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_send_file(
+    roc_request: &roc_http::RequestToAndFromHost,
+    reader: RocBox<()>,
+) -> RocResult<roc_http::ResponseToAndFromHost, roc_http::HttpErr> {
+    let reader: &mut std::io::BufReader<std::fs::File> =
+        roc_std_heap::ThreadSafeRefcountedResourceHeap::box_to_resource(reader);
 
-    match response_res {
-        Ok(response) => {
-            let status = response.status();
+    let body = match roc_http::FileBody::from_reader(reader) {
+        Ok(body) => body,
+        Err(err) => {
+            return RocResult::err(roc_http::HttpErr::new(
+                roc_http::HttpErrTag::InvalidRequest,
+                &err.to_string(),
+            ))
+        }
+    };
 
-            let headers = RocList::from_iter(response.headers().iter().map(|(name, value)| {
-                roc_http::Header::new(name.as_str(), value.to_str().unwrap_or_default())
-            }));
+    TOKIO_RUNTIME.with(|rt| {
+        let request = match roc_request.to_hyper_request_with_body(box_body(body)) {
+            Ok(r) => r,
+            Err(err) => return RocResult::err(err.into()),
+        };
 
-            let status = status.as_u16();
+        let timeout = roc_request.has_timeout().map(Duration::from_millis);
+        let options = SendOptions::of(roc_request);
+        let sent = async_send_request(request, None, &options);
 
-            let bytes_res =
-                response.into_body().collect().await.map(|collected| collected.to_bytes());
+        match rt.block_on(with_timeout(timeout, sent)) {
+            Ok(response) => RocResult::ok(response),
+            Err(err) => RocResult::err(err),
+        }
+    })
+}
 
-            match bytes_res {
-                Ok(bytes) => {
-                    let body: RocList<u8> = RocList::from_iter(bytes);
+/// Builds the request to send, the body is returned as well so it can be sent again when following a redirect.
+fn to_client_request(
+    roc_request: &roc_http::RequestToAndFromHost,
+) -> Result<(hyper::Request<HttpBody>, Bytes), roc_http::HttpErr> {
+    let body = Bytes::copy_from_slice(roc_request.body.as_slice());
 
-                    roc_http::ResponseToAndFromHost {
-                        body,
-                        status,
-                        headers,
-                    }
-                },
-                Err(_) => {
-                    roc_http::ResponseToAndFromHost {
-                        status: 500,
-                        headers: RocList::empty(),
-                        body: roc_http::REQUEST_BAD_BODY.into(),
-                    }
+    let request = roc_request
+        .to_hyper_request_with_body(box_body(http_body_util::Full::new(body.clone())))?;
+
+    Ok((request, body))
+}
+
+fn box_body<B>(body: B) -> HttpBody
+where
+    B: hyper::body::Body<Data = Bytes> + Send + Sync + 'static,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    body.map_err(Into::into).boxed()
+}
+
+/// Sends the request and returns as soon as the headers are received, the body is read with
+/// [roc_fx_http_stream_next_chunk] or [roc_fx_http_stream_to_file].
+#[no_mangle]
+pub extern "C" fn roc_fx_http_send_streaming(
+    roc_request: &roc_http::RequestToAndFromHost,
+) -> RocResult<roc_http::ResponseStreamToHost, roc_http::HttpErr> {
+    TOKIO_RUNTIME.with(|rt| {
+        let (request, body) = match to_client_request(roc_request) {
+            Ok(request_and_body) => request_and_body,
+            Err(err) => return RocResult::err(err),
+        };
+
+        let timeout = roc_request.has_timeout().map(Duration::from_millis);
+        let options = SendOptions::of(roc_request);
+        let sent = send_following_redirects(request, Some(body), &options);
+
+        let followed = match rt.block_on(with_timeout(timeout, sent)) {
+            Ok(followed) => followed,
+            Err(err) => return RocResult::err(err),
+        };
+
+        let status = followed.response.status().as_u16();
+        let version = followed.response.version().into();
+        let headers = roc_headers(followed.response.headers());
+        let final_uri = followed.final_uri.to_string().as_str().into();
+        let redirect_chain = uris_to_roc(&followed.redirect_chain);
+
+        let stream = roc_http::ResponseStream {
+            body: followed.response.into_body(),
+            timeout,
+        };
+
+        match roc_http::response_stream_heap().alloc_for(stream) {
+            Ok(body) => RocResult::ok(roc_http::ResponseStreamToHost {
+                body,
+                final_uri,
+                headers,
+                redirect_chain,
+                status,
+                version,
+            }),
+            Err(err) => RocResult::err(roc_http::HttpErr::new(
+                roc_http::HttpErrTag::Other,
+                &err.to_string(),
+            )),
+        }
+    })
+}
+
+/// Returns the next part of the body, an empty list means the whole body was received.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_stream_next_chunk(
+    data: RocBox<()>,
+) -> RocResult<RocList<u8>, roc_http::HttpErr> {
+    let stream: &mut roc_http::ResponseStream =
+        roc_std_heap::ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    let next = TOKIO_RUNTIME
+        .with(|rt| rt.block_on(with_timeout(stream.timeout, next_data_frame(&mut stream.body))));
+
+    match next {
+        Ok(Some(data)) => RocResult::ok(RocList::from_slice(&data)),
+        Ok(None) => RocResult::ok(RocList::empty()),
+        Err(err) => RocResult::err(err),
+    }
+}
+
+/// Writes the rest of the body to a file and returns the number of bytes written.
+/// The body goes to a temporary file that only replaces the file once the whole body was received.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_stream_to_file(
+    data: RocBox<()>,
+    roc_path: &RocList<u8>,
+) -> RocResult<u64, roc_http::StreamToFileErr> {
+    use std::io::Write;
+
+    let stream: &mut roc_http::ResponseStream =
+        roc_std_heap::ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    let path = roc_file::path_from_roc_path(roc_path);
+
+    let result = roc_file::write_atomic_with(&path, |file| {
+        let mut file = std::io::BufWriter::new(file);
+        let mut written: u64 = 0;
+
+        loop {
+            let next = TOKIO_RUNTIME.with(|rt| {
+                rt.block_on(with_timeout(stream.timeout, next_data_frame(&mut stream.body)))
+            });
+
+            match next.map_err(roc_http::StreamToFileErr::Http)? {
+                Some(data) => {
+                    file.write_all(&data)?;
+                    written += data.len() as u64;
                 }
+                None => break,
             }
         }
-        Err(err) => {
-            // TODO match on the error type to provide more specific responses with appropriate status codes
-            /*use std::error::Error;
-            let err_source_opt = err.source();*/
-
-            roc_http::ResponseToAndFromHost {
-                status: 500,
-                headers: RocList::empty(),
-                body: format!("ERROR:\n{}", err).as_bytes().into(),
+
+        file.flush()?;
+        Ok(written)
+    });
+
+    match result {
+        Ok(written) => RocResult::ok(written),
+        Err(err) => RocResult::err(err),
+    }
+}
+
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl std::future::Future<Output = Result<T, roc_http::HttpErr>>,
+) -> Result<T, roc_http::HttpErr> {
+    match timeout {
+        Some(time_limit) => tokio::time::timeout(time_limit, future)
+            .await
+            .unwrap_or_else(|_elapsed| {
+                Err(roc_http::HttpErr::new(roc_http::HttpErrTag::Timeout, ""))
+            }),
+        None => future.await,
+    }
+}
+
+/// Skips trailers and empty frames, returns `None` at the end of the body.
+async fn next_data_frame(
+    body: &mut hyper::body::Incoming,
+) -> Result<Option<Bytes>, roc_http::HttpErr> {
+    while let Some(frame) = body.frame().await {
+        let frame =
+            frame.map_err(|_| roc_http::HttpErr::new(roc_http::HttpErrTag::BadBody, ""))?;
+
+        if let Ok(data) = frame.into_data() {
+            if !data.is_empty() {
+                return Ok(Some(data));
             }
         }
     }
+
+    Ok(None)
+}
+
+/// Sets how long idle connections are kept open and how many per host, 0 for `idle_timeout_ms` means forever.
+/// The connections of the current client are closed, the next request creates a client with the new options.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_configure_pool(idle_timeout_ms: u64, max_idle_per_host: u64) {
+    let options = HttpPoolOptions {
+        idle_timeout: if idle_timeout_ms > 0 {
+            Some(Duration::from_millis(idle_timeout_ms))
+        } else {
+            None
+        },
+        max_idle_per_host: usize::try_from(max_idle_per_host).unwrap_or(usize::MAX),
+    };
+
+    HTTP_POOL_OPTIONS.with(|cell| cell.set(options));
+    HTTP_CLIENTS.with(|cell| cell.borrow_mut().clear());
+}
+
+/// Sets the certificates that are trusted and the client certificate for https requests.
+/// Like [roc_fx_http_configure_pool], the next request creates a client with the new configuration.
+#[no_mangle]
+pub extern "C" fn roc_fx_http_configure_tls(
+    options: &roc_http::tls::TlsOptionsFromHost,
+) -> RocResult<(), roc_http::HttpErr> {
+    let config = match roc_http::tls::client_config(options) {
+        Ok(config) => config,
+        Err(msg) => {
+            return RocResult::err(roc_http::HttpErr::new(
+                roc_http::HttpErrTag::TlsError,
+                &msg,
+            ))
+        }
+    };
+
+    if options.insecure_skip_verify {
+        eprintln!(
+            "WARNING: Http.configure_tls! turned off TLS certificate verification. \
+             Any server can pretend to be the one you connect to, only use this for local testing."
+        );
+    }
+
+    HTTP_TLS_CONFIG.with(|cell| cell.replace(Some(config)));
+    HTTP_CLIENTS.with(|cell| cell.borrow_mut().clear());
+
+    RocResult::ok(())
+}
+
+/// A client along with the proxies it connects through.
+#[derive(Clone)]
+struct ProxiedClient {
+    client: HttpClient,
+    proxies: Arc<roc_http::proxy::ProxyRules>,
+}
+
+fn http_client(proxy: &roc_http::proxy::ProxySetting) -> Result<ProxiedClient, roc_http::HttpErr> {
+    HTTP_CLIENTS.with(|cell| {
+        // Returns a clone, which is cheap: the clones share the connection pool
+        cell.borrow_mut().get_or_try_insert_with(proxy, || {
+            build_http_client(HTTP_POOL_OPTIONS.with(Cell::get), proxy)
+        })
+    })
+}
+
+/// The environment variables are read once, when the first request with [roc_http::proxy::ProxySetting::FromEnv] is sent.
+fn build_http_client(
+    options: HttpPoolOptions,
+    proxy: &roc_http::proxy::ProxySetting,
+) -> Result<ProxiedClient, roc_http::HttpErr> {
+    use hyper_rustls::HttpsConnectorBuilder;
+
+    let proxies = match roc_http::proxy::ProxyRules::from_setting(proxy) {
+        Ok(rules) => Arc::new(rules),
+        Err(msg) => {
+            return Err(roc_http::HttpErr::new(
+                roc_http::HttpErrTag::InvalidRequest,
+                &msg,
+            ))
+        }
+    };
+
+    let tls_config = match HTTP_TLS_CONFIG.with(|cell| cell.borrow().clone()) {
+        Some(config) => config,
+        None => roc_http::tls::default_client_config().map_err(|msg| {
+            roc_http::HttpErr::new(roc_http::HttpErrTag::TlsError, &msg)
+        })?,
+    };
+
+    let https = HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http()
+        // Offers h2 and http/1.1 with ALPN, plain http uses HTTP/1.1
+        .enable_all_versions()
+        .wrap_connector(roc_http::proxy::ProxyConnector::new(proxies.clone()));
+
+    let client = Client::builder(TokioExecutor::new())
+        .pool_timer(TokioTimer::new())
+        .pool_idle_timeout(options.idle_timeout)
+        .pool_max_idle_per_host(options.max_idle_per_host)
+        .build(https);
+
+    Ok(ProxiedClient { client, proxies })
+}
+
+async fn send_for_response(
+    mut request: hyper::Request<HttpBody>,
+    proxy: &roc_http::proxy::ProxySetting,
+) -> Result<hyper::Response<hyper::body::Incoming>, roc_http::HttpErr> {
+    let ProxiedClient { client, proxies } = http_client(proxy)?;
+
+    // A forwarded request goes to the proxy itself, so it carries the credentials for the proxy
+    if let Some(authorization) = proxies.forward_authorization(request.uri()) {
+        request
+            .headers_mut()
+            .entry(hyper::header::PROXY_AUTHORIZATION)
+            .or_insert_with(|| authorization.clone());
+    }
+
+    client
+        .request(request)
+        .await
+        .map_err(|err| roc_http::client_err_to_http_err(&err))
+}
+
+fn roc_headers(headers: &hyper::HeaderMap) -> RocList<roc_http::Header> {
+    RocList::from_iter(headers.iter().map(|(name, value)| {
+        roc_http::Header::new(name.as_str(), value.to_str().unwrap_or_default())
+    }))
+}
+
+/// Also decodes the body when the request asked for compressed responses, the `Content-Encoding` header is kept
+/// and `Content-Length` is removed.
+async fn async_send_request(
+    mut request: hyper::Request<HttpBody>,
+    replay_body: Option<Bytes>,
+    options: &SendOptions,
+) -> Result<roc_http::ResponseToAndFromHost, roc_http::HttpErr> {
+    if !options.accept_encoding.is_empty() {
+        request
+            .headers_mut()
+            .entry(hyper::header::ACCEPT_ENCODING)
+            .or_insert_with(|| roc_http::encoding::accept_encoding_header(&options.accept_encoding));
+    }
+
+    let followed = send_following_redirects(request, replay_body, options).await?;
+
+    let status = followed.response.status().as_u16();
+    let version = followed.response.version().into();
+    let (mut parts, body) = followed.response.into_parts();
+
+    let bytes = body
+        .collect()
+        .await
+        .map(|collected| collected.to_bytes())
+        .map_err(|_| roc_http::HttpErr::new(roc_http::HttpErrTag::BadBody, ""))?;
+
+    let bytes = if options.accept_encoding.is_empty() {
+        bytes
+    } else {
+        roc_http::encoding::decode_body(
+            &mut parts.headers,
+            bytes,
+            &options.accept_encoding,
+            roc_http::encoding::MAX_DECODED_BODY_LEN,
+        )
+        .map_err(|_| roc_http::HttpErr::new(roc_http::HttpErrTag::BadBody, ""))?
+    };
+
+    Ok(roc_http::ResponseToAndFromHost {
+        body: RocList::from_iter(bytes),
+        final_uri: followed.final_uri.to_string().as_str().into(),
+        headers: roc_headers(&parts.headers),
+        redirect_chain: uris_to_roc(&followed.redirect_chain),
+        status,
+        version,
+    })
+}
+
+/// How a request is sent, besides the request itself.
+struct SendOptions {
+    redirects: roc_http::redirect::RedirectPolicy,
+    proxy: roc_http::proxy::ProxySetting,
+    /// Only used by [async_send_request], streamed bodies are left as they are sent.
+    accept_encoding: Vec<roc_http::encoding::ContentEncoding>,
+}
+
+impl SendOptions {
+    fn of(roc_request: &roc_http::RequestToAndFromHost) -> SendOptions {
+        SendOptions {
+            redirects: roc_http::redirect::RedirectPolicy {
+                limit: roc_request.redirect_limit,
+                same_origin: roc_request.redirect_same_origin,
+            },
+            proxy: roc_http::proxy::ProxySetting::of(roc_request),
+            accept_encoding: roc_request.accept_encoding.as_slice().to_vec(),
+        }
+    }
+}
+
+/// Follows the redirects `options` allows, see [roc_http::redirect::send_following_redirects].
+async fn send_following_redirects(
+    request: hyper::Request<HttpBody>,
+    replay_body: Option<Bytes>,
+    options: &SendOptions,
+) -> Result<roc_http::redirect::FollowedResponse<hyper::body::Incoming>, roc_http::HttpErr> {
+    roc_http::redirect::send_following_redirects(
+        request,
+        replay_body,
+        options.redirects,
+        |body| box_body(http_body_util::Full::new(body)),
+        |request| send_for_response(request, &options.proxy),
+    )
+    .await
+}
+
+fn uris_to_roc(uris: &[hyper::Uri]) -> RocList<RocStr> {
+    RocList::from_iter(uris.iter().map(|uri| RocStr::from(uri.to_string().as_str())))
 }
 
 #[no_mangle]
@@ -783,6 +1504,18 @@ pub extern "C" fn roc_fx_dir_delete_all(
     roc_file::dir_delete_all(roc_path)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_dir_copy_all(
+    from_path: &RocList<u8>,
+    to_path: &RocList<u8>,
+    overwrite: u8,
+    sym_links: u8,
+    preserve_mode: bool,
+    preserve_times: bool,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::dir_copy_all(from_path, to_path, overwrite, sym_links, preserve_mode, preserve_times)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_hard_link(
     path_original: &RocList<u8>,
@@ -791,6 +1524,35 @@ pub extern "C" fn roc_fx_hard_link(
     roc_file::hard_link(path_original, path_link)
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_sym_link(
+    path_original: &RocList<u8>,
+    path_link: &RocList<u8>,
+) -> RocResult<(), roc_io_error::IOErr> {
+    roc_file::sym_link(path_original, path_link)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_read_link(
+    roc_path: &RocList<u8>,
+) -> RocResult<RocList<u8>, roc_io_error::IOErr> {
+    roc_file::read_link(roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_canonicalize(
+    roc_path: &RocList<u8>,
+) -> RocResult<RocList<u8>, roc_io_error::IOErr> {
+    roc_file::canonicalize(roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_path_glob(
+    pattern: &RocStr,
+) -> RocResult<roc_file::InternalGlobResult, roc_io_error::IOErr> {
+    roc_file::path_glob(pattern)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_current_arch_os() -> roc_env::ReturnArchOS {
     roc_env::current_arch_os()
@@ -801,6 +1563,27 @@ pub extern "C" fn roc_fx_temp_dir() -> RocList<u8> {
     roc_env::temp_dir()
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_temp_file_create(
+    prefix: &RocStr,
+    suffix: &RocStr,
+) -> RocResult<RocList<u8>, IOErr> {
+    roc_file::temp_file_create(prefix, suffix)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_temp_dir_create(
+    prefix: &RocStr,
+    suffix: &RocStr,
+) -> RocResult<RocList<u8>, IOErr> {
+    roc_file::temp_dir_create(prefix, suffix)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_temp_keep(roc_path: &RocList<u8>) {
+    roc_file::temp_keep(roc_path)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_get_locale() -> RocResult<RocStr, ()> {
     roc_env::get_locale()
@@ -821,6 +1604,37 @@ pub extern "C" fn roc_fx_random_u32() -> RocResult<u32, IOErr> {
     roc_random::random_u32()
 }
 
+#[no_mangle]
+pub extern "C" fn roc_fx_watcher_new() -> RocResult<RocBox<()>, IOErr> {
+    roc_watcher::watcher_new()
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_watcher_watch(
+    watcher: RocBox<()>,
+    roc_path: &RocList<u8>,
+    recursive: bool,
+) -> RocResult<(), IOErr> {
+    roc_watcher::watcher_watch(watcher, roc_path, recursive)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_watcher_unwatch(
+    watcher: RocBox<()>,
+    roc_path: &RocList<u8>,
+) -> RocResult<(), IOErr> {
+    roc_watcher::watcher_unwatch(watcher, roc_path)
+}
+
+#[no_mangle]
+pub extern "C" fn roc_fx_watcher_next(
+    watcher: RocBox<()>,
+    timeout_ms: u64,
+    debounce_ms: u64,
+) -> RocResult<RocList<roc_watcher::InternalWatchEvent>, IOErr> {
+    roc_watcher::watcher_next(watcher, timeout_ms, debounce_ms)
+}
+
 #[no_mangle]
 pub extern "C" fn roc_fx_sqlite_bind(
     stmt: RocBox<()>,
@@ -0,0 +1,249 @@
+//! Decoding compressed response bodies, for the encodings a request lists in `Accept-Encoding`.
+use bytes::Bytes;
+use roc_std::RocRefcounted;
+use std::io::{ErrorKind, Read};
+
+/// The variants are sorted alphabetically to match the tag ids roc uses.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+#[repr(u8)]
+pub enum ContentEncoding {
+    Br = 0,
+    Deflate = 1,
+    Gzip = 2,
+    Zstd = 3,
+}
+
+roc_std::roc_refcounted_noop_impl!(ContentEncoding);
+
+impl ContentEncoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentEncoding::Br => "br",
+            ContentEncoding::Deflate => "deflate",
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Zstd => "zstd",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<ContentEncoding> {
+        match name {
+            "br" => Some(ContentEncoding::Br),
+            "deflate" => Some(ContentEncoding::Deflate),
+            "gzip" | "x-gzip" => Some(ContentEncoding::Gzip),
+            "zstd" => Some(ContentEncoding::Zstd),
+            _ => None,
+        }
+    }
+
+    fn decoder<'a>(self, compressed: &'a [u8]) -> std::io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            ContentEncoding::Br => Box::new(brotli_decompressor::Decompressor::new(compressed, 4096)),
+            // This should be zlib, but some servers send a raw deflate stream
+            ContentEncoding::Deflate if is_zlib_header(compressed) => {
+                Box::new(flate2::read::ZlibDecoder::new(compressed))
+            }
+            ContentEncoding::Deflate => Box::new(flate2::read::DeflateDecoder::new(compressed)),
+            ContentEncoding::Gzip => Box::new(flate2::read::MultiGzDecoder::new(compressed)),
+            ContentEncoding::Zstd => Box::new(
+                ruzstd::decoding::StreamingDecoder::new(compressed)
+                    .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err.to_string()))?,
+            ),
+        })
+    }
+}
+
+fn is_zlib_header(bytes: &[u8]) -> bool {
+    match bytes {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && ((u16::from(*cmf) << 8) | u16::from(*flg)) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// The value for the `Accept-Encoding` header, in the order of the roc list.
+pub fn accept_encoding_header(encodings: &[ContentEncoding]) -> hyper::header::HeaderValue {
+    let names: Vec<&str> = encodings.iter().map(|encoding| encoding.name()).collect();
+
+    hyper::header::HeaderValue::from_str(&names.join(", ")).expect("encoding names are valid header values")
+}
+
+/// Decoded bodies larger than this are rejected, so a small compressed body can not use up all the memory.
+pub const MAX_DECODED_BODY_LEN: usize = 1024 * 1024 * 1024;
+
+/// Undoes the `Content-Encoding` of the response, the encodings are applied in the order they are listed.
+/// The body is returned as is when it uses an encoding that is not in `accepted`, the server did not respect `Accept-Encoding` then.
+///
+/// A decoded body longer than `max_len` is an error. The `Content-Length` header is removed
+/// when the body is decoded, it is the length of the encoded body.
+pub fn decode_body(
+    headers: &mut hyper::HeaderMap,
+    body: Bytes,
+    accepted: &[ContentEncoding],
+    max_len: usize,
+) -> std::io::Result<Bytes> {
+    let mut encodings = Vec::new();
+
+    for value in headers.get_all(hyper::header::CONTENT_ENCODING) {
+        let Ok(value) = value.to_str() else {
+            return Ok(body);
+        };
+
+        for name in value.split(',').map(|name| name.trim().to_ascii_lowercase()) {
+            if name.is_empty() || name == "identity" {
+                continue;
+            }
+
+            match ContentEncoding::from_name(&name) {
+                Some(encoding) if accepted.contains(&encoding) => encodings.push(encoding),
+                _ => return Ok(body),
+            }
+        }
+    }
+
+    // A HEAD request or a 204 has no body to decode
+    if body.is_empty() || encodings.is_empty() {
+        return Ok(body);
+    }
+
+    let mut decoded = body;
+
+    for encoding in encodings.into_iter().rev() {
+        let mut buf = Vec::new();
+        // One byte more than allowed, to find out if there is more
+        let limit = max_len as u64 + 1;
+        encoding.decoder(&decoded)?.take(limit).read_to_end(&mut buf)?;
+
+        if buf.len() > max_len {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("The decoded body is longer than {} bytes", max_len),
+            ));
+        }
+
+        decoded = Bytes::from(buf);
+    }
+
+    headers.remove(hyper::header::CONTENT_LENGTH);
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const TEXT: &[u8] = b"Hello, compressed world! Hello, compressed world! Hello, compressed world!";
+
+    fn headers(content_encoding: &str, content_length: usize) -> hyper::HeaderMap {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert(
+            hyper::header::CONTENT_ENCODING,
+            content_encoding.parse().unwrap(),
+        );
+        headers.insert(hyper::header::CONTENT_LENGTH, content_length.into());
+        headers
+    }
+
+    fn decode(content_encoding: &str, compressed: Vec<u8>) -> (Bytes, hyper::HeaderMap) {
+        let mut headers = headers(content_encoding, compressed.len());
+        let all = [
+            ContentEncoding::Br,
+            ContentEncoding::Deflate,
+            ContentEncoding::Gzip,
+            ContentEncoding::Zstd,
+        ];
+        let decoded =
+            decode_body(&mut headers, Bytes::from(compressed), &all, MAX_DECODED_BODY_LEN).unwrap();
+
+        (decoded, headers)
+    }
+
+    #[test]
+    fn deflate_with_zlib_header() {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(TEXT).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(is_zlib_header(&compressed));
+
+        let (decoded, headers) = decode("deflate", compressed);
+
+        assert_eq!(decoded, TEXT);
+        assert!(headers.get(hyper::header::CONTENT_LENGTH).is_none());
+        assert_eq!(headers[hyper::header::CONTENT_ENCODING], "deflate");
+    }
+
+    #[test]
+    fn raw_deflate() {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(TEXT).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(!is_zlib_header(&compressed));
+
+        assert_eq!(decode("deflate", compressed).0, TEXT);
+    }
+
+    #[test]
+    fn br() {
+        // `printf 'Hello, br!' | brotli`
+        let compressed = vec![
+            0x8b, 0x04, 0x80, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x62, 0x72, 0x21, 0x03,
+        ];
+
+        assert_eq!(decode("br", compressed).0, &b"Hello, br!"[..]);
+    }
+
+    #[test]
+    fn zstd() {
+        let compressed =
+            ruzstd::encoding::compress_to_vec(TEXT, ruzstd::encoding::CompressionLevel::Fastest);
+
+        assert_eq!(decode("zstd", compressed).0, TEXT);
+    }
+
+    #[test]
+    fn gzip_then_zstd() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(TEXT).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        let compressed = ruzstd::encoding::compress_to_vec(
+            gzipped.as_slice(),
+            ruzstd::encoding::CompressionLevel::Fastest,
+        );
+
+        assert_eq!(decode("gzip, zstd", compressed).0, TEXT);
+    }
+
+    #[test]
+    fn not_accepted_is_left_as_is() {
+        let mut headers = headers("br", 3);
+        let body = Bytes::from_static(b"abc");
+
+        let decoded =
+            decode_body(&mut headers, body.clone(), &[ContentEncoding::Gzip], MAX_DECODED_BODY_LEN)
+                .unwrap();
+
+        assert_eq!(decoded, body);
+        assert_eq!(headers[hyper::header::CONTENT_LENGTH], "3");
+    }
+
+    #[test]
+    fn longer_than_the_limit() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&[0; 1000]).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut headers = headers("gzip", compressed.len());
+
+        let exact = decode_body(
+            &mut headers.clone(),
+            Bytes::from(compressed.clone()),
+            &[ContentEncoding::Gzip],
+            1000,
+        );
+        let too_long =
+            decode_body(&mut headers, Bytes::from(compressed), &[ContentEncoding::Gzip], 999);
+
+        assert_eq!(exact.unwrap().len(), 1000);
+        assert_eq!(too_long.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -7,9 +7,10 @@ use std::net::TcpStream;
 use std::sync::OnceLock;
 use bytes::Bytes;
 
-pub const REQUEST_TIMEOUT_BODY: &[u8] = "RequestTimeout".as_bytes();
-pub const REQUEST_NETWORK_ERR: &[u8] = "Network Error".as_bytes();
-pub const REQUEST_BAD_BODY: &[u8] = "Bad Body".as_bytes();
+pub mod encoding;
+pub mod proxy;
+pub mod redirect;
+pub mod tls;
 
 pub fn heap() -> &'static ThreadSafeRefcountedResourceHeap<BufReader<TcpStream>> {
     // TODO: Should this be a BufReader and BufWriter of the tcp stream?
@@ -27,30 +28,64 @@ pub fn heap() -> &'static ThreadSafeRefcountedResourceHeap<BufReader<TcpStream>>
     })
 }
 
+/// The body of a response that is received chunk by chunk, instead of all at once.
+pub struct ResponseStream {
+    pub body: hyper::body::Incoming,
+    /// The `timeout_ms` of the request, it limits the wait for each chunk.
+    pub timeout: Option<std::time::Duration>,
+}
+
+pub fn response_stream_heap() -> &'static ThreadSafeRefcountedResourceHeap<ResponseStream> {
+    static RESPONSE_STREAM_HEAP: OnceLock<ThreadSafeRefcountedResourceHeap<ResponseStream>> =
+        OnceLock::new();
+    RESPONSE_STREAM_HEAP.get_or_init(|| {
+        let default_max = 65536;
+        let max_response_streams = env::var("ROC_BASIC_CLI_MAX_HTTP_RESPONSE_STREAMS")
+            .map(|v| v.parse().unwrap_or(default_max))
+            .unwrap_or(default_max);
+        ThreadSafeRefcountedResourceHeap::new(max_response_streams)
+            .expect("Failed to allocate mmap for http response stream handle references.")
+    })
+}
+
 const UNEXPECTED_EOF_ERROR: &str = "UnexpectedEof";
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[repr(C, align(8))]
 pub struct RequestToAndFromHost {
+    /// The encodings to ask for with `Accept-Encoding` and to decode, empty to leave the body as it is sent.
+    pub accept_encoding: RocList<encoding::ContentEncoding>,
     pub body: RocList<u8>,
     pub headers: RocList<Header>,
     pub method: u64,
     pub method_ext: RocStr,
+    /// The proxy to use instead of the one from the environment, empty for no proxy.
+    pub proxy_uri: RocStr,
+    /// How many redirects to follow, 0 means they are returned like any other response.
+    pub redirect_limit: u64,
     pub timeout_ms: u64,
     pub uri: RocStr,
+    /// Use the proxy from the `http_proxy`, `https_proxy` and `no_proxy` environment variables.
+    pub proxy_from_env: bool,
+    /// Only follow redirects to the scheme, host and port of `uri`.
+    pub redirect_same_origin: bool,
 }
 
 impl RocRefcounted for RequestToAndFromHost {
     fn inc(&mut self) {
+        self.accept_encoding.inc();
         self.body.inc();
         self.headers.inc();
         self.method_ext.inc();
+        self.proxy_uri.inc();
         self.uri.inc();
     }
     fn dec(&mut self) {
+        self.accept_encoding.dec();
         self.body.dec();
         self.headers.dec();
         self.method_ext.dec();
+        self.proxy_uri.dec();
         self.uri.dec();
     }
     fn is_refcounted() -> bool {
@@ -79,12 +114,17 @@ impl From<hyper::Request<String>> for RequestToAndFromHost {
         let uri = hyper_req.uri().to_string().as_str().into();
 
         RequestToAndFromHost {
+            accept_encoding: RocList::empty(),
             body,
             headers,
             method,
             method_ext,
+            proxy_uri: RocStr::empty(),
+            redirect_limit: 0,
             timeout_ms,
             uri,
+            proxy_from_env: false,
+            redirect_same_origin: false,
         }
     }
 }
@@ -134,6 +174,13 @@ impl RequestToAndFromHost {
     }
 
     pub fn to_hyper_request(&self) -> Result<hyper::Request<http_body_util::Full<Bytes>>, hyper::http::Error> {
+        let bytes: http_body_util::Full<Bytes> = http_body_util::Full::new(self.body.as_slice().to_vec().into());
+
+        self.to_hyper_request_with_body(bytes)
+    }
+
+    /// Uses `body` instead of the body of the roc request, which is ignored.
+    pub fn to_hyper_request_with_body<B>(&self, body: B) -> Result<hyper::Request<B>, hyper::http::Error> {
         let method: hyper::Method = self.as_hyper_method();
         let mut req_builder = hyper::Request::builder()
             .method(method)
@@ -154,9 +201,7 @@ impl RequestToAndFromHost {
             req_builder = req_builder.header("Content-Type", "text/plain");
         }
 
-        let bytes: http_body_util::Full<Bytes> = http_body_util::Full::new(self.body.as_slice().to_vec().into());
-
-        req_builder.body(bytes)
+        req_builder.body(body)
     }
 }
 
@@ -164,30 +209,69 @@ impl RequestToAndFromHost {
 #[repr(C)]
 pub struct ResponseToAndFromHost {
     pub body: RocList<u8>,
+    /// The uri that gave this response, after following redirects.
+    pub final_uri: RocStr,
     pub headers: RocList<Header>,
+    /// The uris that answered with a redirect, in the order they were followed.
+    pub redirect_chain: RocList<RocStr>,
     pub status: u16,
+    /// The version of HTTP the response was received with, HTTP/2 is used when the server picks it during the TLS handshake.
+    pub version: HttpVersion,
 }
 
 impl RocRefcounted for ResponseToAndFromHost {
     fn inc(&mut self) {
         self.body.inc();
+        self.final_uri.inc();
         self.headers.inc();
+        self.redirect_chain.inc();
     }
     fn dec(&mut self) {
         self.body.dec();
+        self.final_uri.dec();
         self.headers.dec();
+        self.redirect_chain.dec();
     }
     fn is_refcounted() -> bool {
         true
     }
 }
 
-impl From<hyper::http::Error> for ResponseToAndFromHost {
-    fn from(err: hyper::http::Error) -> Self {
-        ResponseToAndFromHost {
-            status: 500,
-            headers: RocList::empty(),
-            body: err.to_string().as_bytes().into(),
+/// The status and headers of a response, with its body still to be received through `body`.
+/// The fields are sorted by alignment and then alphabetically to match the layout of the roc record.
+#[derive(Debug)]
+#[repr(C)]
+pub struct ResponseStreamToHost {
+    pub body: RocBox<()>,
+    pub final_uri: RocStr,
+    pub headers: RocList<Header>,
+    pub redirect_chain: RocList<RocStr>,
+    pub status: u16,
+    pub version: HttpVersion,
+}
+
+/// The variants are sorted alphabetically to match the tag ids roc uses.
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+#[repr(u8)]
+pub enum HttpVersion {
+    Http09 = 0,
+    Http10 = 1,
+    #[default]
+    Http11 = 2,
+    Http2 = 3,
+    Http3 = 4,
+}
+
+roc_std::roc_refcounted_noop_impl!(HttpVersion);
+
+impl From<hyper::Version> for HttpVersion {
+    fn from(version: hyper::Version) -> Self {
+        match version {
+            hyper::Version::HTTP_09 => HttpVersion::Http09,
+            hyper::Version::HTTP_10 => HttpVersion::Http10,
+            hyper::Version::HTTP_2 => HttpVersion::Http2,
+            hyper::Version::HTTP_3 => HttpVersion::Http3,
+            _ => HttpVersion::Http11,
         }
     }
 }
@@ -218,6 +302,323 @@ impl From<ResponseToAndFromHost> for hyper::StatusCode {
     }
 }
 
+const FILE_BODY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A request body that is read from a file while it is sent, so the file never has to fit in memory.
+/// For regular files the length is known and sent as Content-Length, for pipes and the like chunked transfer encoding is used.
+///
+/// The file is read with tokio, which reads on its blocking thread pool, so a slow pipe does not stall the runtime.
+pub struct FileBody {
+    file: tokio::fs::File,
+    /// What the reader this body was created from had already read from the file.
+    buffered: Option<Bytes>,
+    /// The chunks are split off this buffer, its memory is reused once hyper has sent them.
+    buf: bytes::BytesMut,
+    /// How much of a regular file is left to read after `buffered`, for other files the length is unknown.
+    remaining: Option<u64>,
+}
+
+impl FileBody {
+    /// Takes over what is left in the reader, the file is shared so the reader is at the end of the file afterwards.
+    pub fn from_reader(reader: &mut BufReader<std::fs::File>) -> std::io::Result<FileBody> {
+        use std::io::Seek;
+
+        let mut file = reader.get_ref().try_clone()?;
+        let buffered = Bytes::copy_from_slice(reader.buffer());
+        reader.consume(buffered.len());
+
+        let metadata = file.metadata()?;
+        let remaining = if metadata.is_file() {
+            let position = file.stream_position()?;
+            Some(metadata.len().saturating_sub(position))
+        } else {
+            None
+        };
+
+        Ok(FileBody {
+            file: tokio::fs::File::from_std(file),
+            buffered: Some(buffered),
+            buf: bytes::BytesMut::new(),
+            remaining,
+        })
+    }
+}
+
+/// The file of a [FileBody] changed its size while it was sent, so it no longer matches the Content-Length.
+#[derive(Debug)]
+pub struct FileSizeChanged {
+    pub grew: bool,
+}
+
+impl std::fmt::Display for FileSizeChanged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.grew {
+            write!(f, "The file got longer while it was sent")
+        } else {
+            write!(f, "The file got shorter while it was sent")
+        }
+    }
+}
+
+impl std::error::Error for FileSizeChanged {}
+
+impl hyper::body::Body for FileBody {
+    type Data = Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<hyper::body::Frame<Bytes>, std::io::Error>>> {
+        use bytes::BufMut;
+        use std::task::Poll;
+
+        if let Some(buffered) = self.buffered.take().filter(|buffered| !buffered.is_empty()) {
+            return Poll::Ready(Some(Ok(hyper::body::Frame::data(buffered))));
+        }
+
+        // Once the length is read, a single byte is asked for to find out if the file grew
+        let limit = match self.remaining {
+            Some(remaining) => remaining.clamp(1, FILE_BODY_CHUNK_SIZE as u64) as usize,
+            None => FILE_BODY_CHUNK_SIZE,
+        };
+
+        let this = &mut *self;
+        this.buf.reserve(limit);
+
+        let read = {
+            // SAFETY: the ReadBuf only hands out the bytes that were written to
+            let spare = unsafe { this.buf.chunk_mut().as_uninit_slice_mut() };
+            let mut read_buf = tokio::io::ReadBuf::uninit(&mut spare[..limit]);
+
+            let file = std::pin::Pin::new(&mut this.file);
+
+            match tokio::io::AsyncRead::poll_read(file, cx, &mut read_buf) {
+                Poll::Ready(Ok(())) => read_buf.filled().len(),
+                Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+                Poll::Pending => return Poll::Pending,
+            }
+        };
+
+        match this.remaining {
+            Some(remaining) if read == 0 && remaining > 0 => {
+                return Poll::Ready(Some(Err(file_size_changed(false))))
+            }
+            Some(0) if read > 0 => return Poll::Ready(Some(Err(file_size_changed(true)))),
+            Some(remaining) => this.remaining = Some(remaining - read as u64),
+            None => {}
+        }
+
+        if read == 0 {
+            return Poll::Ready(None);
+        }
+
+        // SAFETY: poll_read initialized this many bytes of the spare capacity
+        unsafe { this.buf.advance_mut(read) };
+
+        Poll::Ready(Some(Ok(hyper::body::Frame::data(this.buf.split().freeze()))))
+    }
+
+    fn size_hint(&self) -> hyper::body::SizeHint {
+        let buffered = self.buffered.as_ref().map_or(0, |buffered| buffered.len() as u64);
+
+        self.remaining.map_or_else(hyper::body::SizeHint::default, |remaining| {
+            hyper::body::SizeHint::with_exact(buffered + remaining)
+        })
+    }
+}
+
+fn file_size_changed(grew: bool) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, FileSizeChanged { grew })
+}
+
+/// The variants are sorted alphabetically to match the tag ids roc uses.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Debug)]
+#[repr(u8)]
+pub enum HttpErrTag {
+    BadBody = 0,
+    ConnectionRefused = 1,
+    DnsFailure = 2,
+    InvalidRequest = 3,
+    Other = 4,
+    Timeout = 5,
+    TlsError = 6,
+}
+
+roc_std::roc_refcounted_noop_impl!(HttpErrTag);
+
+/// Why a request did not get a response, `msg` is only used by `InvalidRequest`, `Other` and `TlsError`.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[repr(C)]
+pub struct HttpErr {
+    pub msg: RocStr,
+    pub tag: HttpErrTag,
+}
+
+impl RocRefcounted for HttpErr {
+    fn inc(&mut self) {
+        self.msg.inc();
+    }
+    fn dec(&mut self) {
+        self.msg.dec();
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+impl HttpErr {
+    pub fn new(tag: HttpErrTag, msg: &str) -> HttpErr {
+        HttpErr {
+            msg: msg.into(),
+            tag,
+        }
+    }
+}
+
+/// Why writing a response body to a file failed, `[Http HttpErr, Io IOErr]` in Roc.
+#[repr(C)]
+pub struct StreamToFileErr {
+    payload: union_StreamToFileErr,
+    discriminant: StreamToFileErrDiscriminant,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StreamToFileErrDiscriminant {
+    Http = 0,
+    Io = 1,
+}
+
+#[allow(non_snake_case)]
+#[repr(C, align(8))]
+pub union union_StreamToFileErr {
+    Http: core::mem::ManuallyDrop<HttpErr>,
+    Io: core::mem::ManuallyDrop<roc_io_error::IOErr>,
+}
+
+#[allow(non_snake_case)]
+impl StreamToFileErr {
+    pub fn Http(payload: HttpErr) -> Self {
+        Self {
+            discriminant: StreamToFileErrDiscriminant::Http,
+            payload: union_StreamToFileErr {
+                Http: core::mem::ManuallyDrop::new(payload),
+            },
+        }
+    }
+
+    pub fn Io(payload: roc_io_error::IOErr) -> Self {
+        Self {
+            discriminant: StreamToFileErrDiscriminant::Io,
+            payload: union_StreamToFileErr {
+                Io: core::mem::ManuallyDrop::new(payload),
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for StreamToFileErr {
+    fn from(err: std::io::Error) -> Self {
+        StreamToFileErr::Io(err.into())
+    }
+}
+
+impl Drop for StreamToFileErr {
+    fn drop(&mut self) {
+        match self.discriminant {
+            StreamToFileErrDiscriminant::Http => unsafe {
+                core::mem::ManuallyDrop::drop(&mut self.payload.Http)
+            },
+            StreamToFileErrDiscriminant::Io => unsafe {
+                core::mem::ManuallyDrop::drop(&mut self.payload.Io)
+            },
+        }
+    }
+}
+
+impl RocRefcounted for StreamToFileErr {
+    fn inc(&mut self) {
+        match self.discriminant {
+            StreamToFileErrDiscriminant::Http => unsafe { (*self.payload.Http).inc() },
+            StreamToFileErrDiscriminant::Io => unsafe { (*self.payload.Io).inc() },
+        }
+    }
+    fn dec(&mut self) {
+        match self.discriminant {
+            StreamToFileErrDiscriminant::Http => unsafe { (*self.payload.Http).dec() },
+            StreamToFileErrDiscriminant::Io => unsafe { (*self.payload.Io).dec() },
+        }
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+impl From<hyper::http::Error> for HttpErr {
+    fn from(err: hyper::http::Error) -> Self {
+        HttpErr::new(HttpErrTag::InvalidRequest, &err.to_string())
+    }
+}
+
+/// Finds out why sending a request failed by going through the chain of errors that caused it.
+/// hyper wraps the errors of the connector, which wraps the io and TLS errors.
+pub fn client_err_to_http_err(err: &(dyn std::error::Error + 'static)) -> HttpErr {
+    let mut source = Some(err);
+
+    while let Some(current) = source {
+        if current.downcast_ref::<proxy::DnsError>().is_some() {
+            return HttpErr::new(HttpErrTag::DnsFailure, "");
+        }
+
+        if let Some(tls_err) = current.downcast_ref::<rustls::Error>() {
+            return HttpErr::new(HttpErrTag::TlsError, &tls_err.to_string());
+        }
+
+        if let Some(io_err) = current.downcast_ref::<std::io::Error>() {
+            if let Some(changed) = io_err
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<FileSizeChanged>())
+            {
+                return HttpErr::new(HttpErrTag::InvalidRequest, &changed.to_string());
+            }
+
+            if let Some(tls_err) = io_err
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+            {
+                return HttpErr::new(HttpErrTag::TlsError, &tls_err.to_string());
+            }
+
+            match io_err.kind() {
+                ErrorKind::ConnectionRefused => {
+                    return HttpErr::new(HttpErrTag::ConnectionRefused, "")
+                }
+                ErrorKind::TimedOut => return HttpErr::new(HttpErrTag::Timeout, ""),
+                _ => {}
+            }
+        }
+
+        source = current.source();
+    }
+
+    HttpErr::new(HttpErrTag::Other, &error_chain_to_string(err))
+}
+
+/// hyper's errors only describe the outermost problem, like "client error (Connect)", the cause is in the sources.
+fn error_chain_to_string(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+
+    while let Some(current) = source {
+        message.push_str(": ");
+        message.push_str(&current.to_string());
+        source = current.source();
+    }
+
+    message
+}
+
 #[derive(Clone, Default, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[repr(C)]
 pub struct Header {
@@ -270,32 +671,28 @@ pub fn tcp_read_up_to(stream: RocBox<()>, bytes_to_read: u64) -> RocResult<RocLi
 
     let mut chunk = stream.take(bytes_to_read);
 
-    //TODO: fill a roc list directly. This is an extra O(n) copy.
-    match chunk.fill_buf() {
-        Ok(received) => {
-            let received = received.to_vec();
-            stream.consume(received.len());
+    let received = match chunk.fill_buf() {
+        Ok(received) => RocList::from(received),
+        Err(err) => return RocResult::err(to_tcp_stream_err(err)),
+    };
+    stream.consume(received.len());
 
-            RocResult::ok(RocList::from(&received[..]))
-        }
-        Err(err) => RocResult::err(to_tcp_stream_err(err)),
-    }
+    RocResult::ok(received)
 }
 
 pub fn tcp_read_exactly(stream: RocBox<()>, bytes_to_read: u64) -> RocResult<RocList<u8>, RocStr> {
     let stream: &mut BufReader<TcpStream> =
         ThreadSafeRefcountedResourceHeap::box_to_resource(stream);
 
-    let mut buffer = Vec::with_capacity(bytes_to_read as usize);
+    let mut buffer = RocList::with_capacity(bytes_to_read as usize);
     let mut chunk = stream.take(bytes_to_read);
 
-    //TODO: fill a roc list directly. This is an extra O(n) copy.
-    match chunk.read_to_end(&mut buffer) {
+    match roc_file::read_to_end(&mut chunk, &mut buffer) {
         Ok(read) => {
             if (read as u64) < bytes_to_read {
                 RocResult::err(UNEXPECTED_EOF_ERROR.into())
             } else {
-                RocResult::ok(RocList::from(&buffer[..]))
+                RocResult::ok(buffer)
             }
         }
         Err(err) => RocResult::err(to_tcp_stream_err(err)),
@@ -0,0 +1,416 @@
+//! Following the redirects of a response, like browsers do.
+//!
+//! The sending itself is left to the caller, so the same rules apply to every kind of request body.
+use crate::HttpErr;
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use hyper::header::HeaderName;
+use hyper::{Method, Request, Response, Uri};
+use std::future::Future;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Which redirects are followed.
+#[derive(Clone, Copy, Debug)]
+pub struct RedirectPolicy {
+    /// How many redirects are followed at most, 0 returns the first response as it is.
+    pub limit: u64,
+    /// Only follow redirects to the origin of the first request.
+    pub same_origin: bool,
+}
+
+/// The last response, after following the redirects the policy allows.
+pub struct FollowedResponse<B> {
+    pub response: Response<B>,
+    pub final_uri: Uri,
+    /// The uris that answered with a redirect, in the order they were followed.
+    pub redirect_chain: Vec<Uri>,
+}
+
+/// Headers that describe the body, they are removed when a redirect drops the body.
+const CONTENT_HEADERS: [HeaderName; 6] = [
+    hyper::header::CONTENT_ENCODING,
+    hyper::header::CONTENT_LANGUAGE,
+    hyper::header::CONTENT_LENGTH,
+    hyper::header::CONTENT_LOCATION,
+    hyper::header::CONTENT_TYPE,
+    hyper::header::TRANSFER_ENCODING,
+];
+
+/// Headers that are not sent along to another origin.
+const CREDENTIAL_HEADERS: [HeaderName; 3] = [
+    hyper::header::AUTHORIZATION,
+    hyper::header::COOKIE,
+    hyper::header::PROXY_AUTHORIZATION,
+];
+
+/// Redirect bodies up to this size are read before the next request is sent.
+const MAX_DRAINED_REDIRECT_BODY: usize = 64 * 1024;
+
+/// Sends `request` with `send` and follows redirects like browsers do: 303 switches to GET, as do 301 and 302 for a POST,
+/// and 307 and 308 send the same method and body again.
+/// `replay_body` is `None` when the body can only be sent once, redirects that need it again are then not followed.
+/// `to_body` turns the body to send again into a request body.
+/// When a redirect is not followed, because of the limit or the policy, the redirect response itself is returned.
+pub async fn send_following_redirects<ReqB, ResB, Fut>(
+    request: Request<ReqB>,
+    mut replay_body: Option<Bytes>,
+    policy: RedirectPolicy,
+    to_body: impl Fn(Bytes) -> ReqB,
+    mut send: impl FnMut(Request<ReqB>) -> Fut,
+) -> Result<FollowedResponse<ResB>, HttpErr>
+where
+    Fut: Future<Output = Result<Response<ResB>, HttpErr>>,
+    ResB: hyper::body::Body,
+    ResB::Error: Into<BoxError>,
+{
+    let first_origin = uri_origin(request.uri());
+    let mut method = request.method().clone();
+    let mut uri = request.uri().clone();
+    let mut headers = request.headers().clone();
+    let mut redirect_chain = Vec::new();
+
+    let mut response = send(request).await?;
+
+    while (redirect_chain.len() as u64) < policy.limit {
+        let Some(next_uri) = redirect_target(&response, &uri) else {
+            break;
+        };
+
+        if policy.same_origin && uri_origin(&next_uri) != first_origin {
+            break;
+        }
+
+        let switch_to_get = match response.status().as_u16() {
+            303 => method != Method::HEAD,
+            301 | 302 => method == Method::POST,
+            _ => false,
+        };
+
+        if switch_to_get {
+            method = Method::GET;
+            replay_body = Some(Bytes::new());
+            for name in CONTENT_HEADERS {
+                headers.remove(name);
+            }
+        }
+
+        let Some(body) = replay_body.clone() else {
+            break;
+        };
+
+        if uri_origin(&next_uri) != uri_origin(&uri) {
+            for name in CREDENTIAL_HEADERS {
+                headers.remove(name);
+            }
+        }
+        // hyper sets it from the new uri
+        headers.remove(hyper::header::HOST);
+
+        redirect_chain.push(std::mem::replace(&mut uri, next_uri));
+
+        let mut next_request = Request::new(to_body(body));
+        *next_request.method_mut() = method.clone();
+        *next_request.uri_mut() = uri.clone();
+        *next_request.headers_mut() = headers.clone();
+
+        drain_redirect_body(response.into_body()).await;
+        response = send(next_request).await?;
+    }
+
+    Ok(FollowedResponse {
+        response,
+        final_uri: uri,
+        redirect_chain,
+    })
+}
+
+/// Reads the body of a redirect, so its connection goes back to the pool and can be reused for the next request.
+/// A larger body is dropped when it turns out to be too long, which closes the connection instead.
+async fn drain_redirect_body<B>(body: B)
+where
+    B: hyper::body::Body,
+    B::Error: Into<BoxError>,
+{
+    _ = http_body_util::Limited::new(body, MAX_DRAINED_REDIRECT_BODY)
+        .collect()
+        .await;
+}
+
+/// Returns where a redirect response points to, or `None` if it is not a redirect.
+fn redirect_target<B>(response: &Response<B>, base: &Uri) -> Option<Uri> {
+    if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+
+    let location = response
+        .headers()
+        .get(hyper::header::LOCATION)?
+        .to_str()
+        .ok()?;
+
+    resolve_location(base, location)
+}
+
+/// Resolves the Location header of a redirect, which is often relative to the uri that was requested.
+/// `.` and `..` segments are removed from the path, like RFC 3986 does for every reference.
+pub fn resolve_location(base: &Uri, location: &str) -> Option<Uri> {
+    // The fragment stays in the client, it is never sent
+    let location = location.split('#').next().unwrap_or_default();
+
+    if let Ok(uri) = location.parse::<Uri>() {
+        if uri.scheme().is_some() {
+            return without_dot_segments(uri);
+        }
+    }
+
+    let scheme = base.scheme_str()?;
+    let authority = base.authority()?.as_str();
+
+    let absolute = if let Some(network_path) = location.strip_prefix("//") {
+        format!("{}://{}", scheme, network_path)
+    } else if location.starts_with('/') {
+        format!("{}://{}{}", scheme, authority, location)
+    } else if location.starts_with('?') {
+        format!("{}://{}{}{}", scheme, authority, base.path(), location)
+    } else {
+        let path = base.path();
+        let dir = &path[..path.rfind('/').map_or(0, |slash| slash + 1)];
+        format!("{}://{}{}{}", scheme, authority, dir, location)
+    };
+
+    without_dot_segments(absolute.parse().ok()?)
+}
+
+fn without_dot_segments(uri: Uri) -> Option<Uri> {
+    let Some(path_and_query) = uri.path_and_query() else {
+        return Some(uri);
+    };
+
+    let path = remove_dot_segments(path_and_query.path());
+    if path == path_and_query.path() {
+        return Some(uri);
+    }
+
+    let path_and_query = match path_and_query.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+
+    let mut parts = uri.into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+/// The `remove_dot_segments` of RFC 3986, for a path that starts with a `/`.
+fn remove_dot_segments(path: &str) -> String {
+    if !path.starts_with('/') {
+        return path.to_string();
+    }
+
+    let mut segments = Vec::new();
+    for segment in path.split('/').skip(1) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let mut normalized: String = segments.iter().flat_map(|segment| ["/", segment]).collect();
+
+    // `/a/b/..` points to the directory `/a/`, not the file `/a`
+    if normalized.is_empty() || matches!(path.rsplit('/').next(), Some(".") | Some("..")) {
+        normalized.push('/');
+    }
+
+    normalized
+}
+
+/// The scheme, host and port of a uri, with the default port filled in.
+pub fn uri_origin(uri: &Uri) -> (String, String, u16) {
+    let scheme = uri.scheme_str().unwrap_or_default().to_ascii_lowercase();
+    let host = uri.host().unwrap_or_default().to_ascii_lowercase();
+    let port = uri
+        .port_u16()
+        .unwrap_or(if scheme == "https" { 443 } else { 80 });
+
+    (scheme, host, port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::Full;
+    use std::cell::RefCell;
+
+    fn resolve(base: &str, location: &str) -> String {
+        resolve_location(&base.parse().unwrap(), location)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn relative_locations() {
+        let base = "https://example.com/a/b/page?x=1";
+
+        assert_eq!(resolve(base, "other"), "https://example.com/a/b/other");
+        assert_eq!(resolve(base, "./other"), "https://example.com/a/b/other");
+        assert_eq!(resolve(base, "../other?y=2"), "https://example.com/a/other?y=2");
+        assert_eq!(resolve(base, "../../../../other"), "https://example.com/other");
+        assert_eq!(resolve(base, ".."), "https://example.com/a/");
+        assert_eq!(resolve(base, "/root/./x/../y"), "https://example.com/root/y");
+        assert_eq!(resolve(base, "?y=2"), "https://example.com/a/b/page?y=2");
+        assert_eq!(resolve(base, "//cdn.example.com/file"), "https://cdn.example.com/file");
+        assert_eq!(resolve(base, "http://other.com/p/../q#top"), "http://other.com/q");
+    }
+
+    #[test]
+    fn origins_fill_in_the_default_port() {
+        let origin = |uri: &str| uri_origin(&uri.parse().unwrap());
+
+        assert_eq!(origin("https://Example.com/a"), origin("https://example.com:443/b"));
+        assert_eq!(origin("http://example.com/"), origin("http://example.com:80/"));
+        assert_ne!(origin("http://example.com/"), origin("https://example.com/"));
+        assert_ne!(origin("https://example.com/"), origin("https://example.com:8443/"));
+    }
+
+    /// A request as the fake server received it.
+    #[derive(Debug)]
+    struct Sent {
+        method: Method,
+        uri: String,
+        headers: hyper::HeaderMap,
+        body: Bytes,
+    }
+
+    /// Sends `request` to a fake server that answers with `responses` in turn, the last one again when they run out.
+    fn follow(
+        request: Request<Bytes>,
+        policy: RedirectPolicy,
+        responses: &[(u16, Option<&str>)],
+    ) -> (FollowedResponse<Full<Bytes>>, Vec<Sent>) {
+        let sent = RefCell::new(Vec::new());
+
+        let send = |request: Request<Bytes>| {
+            let (parts, body) = request.into_parts();
+            let mut sent = sent.borrow_mut();
+            sent.push(Sent {
+                method: parts.method,
+                uri: parts.uri.to_string(),
+                headers: parts.headers,
+                body,
+            });
+
+            let (status, location) = responses[(sent.len() - 1).min(responses.len() - 1)];
+            let mut response = Response::new(Full::new(Bytes::from_static(b"moved")));
+            *response.status_mut() = status.try_into().unwrap();
+            if let Some(location) = location {
+                response
+                    .headers_mut()
+                    .insert(hyper::header::LOCATION, location.parse().unwrap());
+            }
+
+            std::future::ready(Ok(response))
+        };
+
+        let replay_body = Some(request.body().clone());
+        let followed = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(send_following_redirects(request, replay_body, policy, |body| body, send))
+            .unwrap();
+
+        (followed, sent.into_inner())
+    }
+
+    fn post(uri: &str) -> Request<Bytes> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(hyper::header::CONTENT_TYPE, "text/plain")
+            .header(hyper::header::AUTHORIZATION, "Bearer secret")
+            .body(Bytes::from_static(b"data"))
+            .unwrap()
+    }
+
+    const FOLLOW_ALL: RedirectPolicy = RedirectPolicy {
+        limit: 10,
+        same_origin: false,
+    };
+
+    #[test]
+    fn see_other_switches_to_get_without_a_body() {
+        let (followed, sent) = follow(
+            post("http://example.com/form"),
+            FOLLOW_ALL,
+            &[(303, Some("/done")), (200, None)],
+        );
+
+        assert_eq!(followed.response.status(), 200);
+        assert_eq!(followed.final_uri, "http://example.com/done");
+        assert_eq!(followed.redirect_chain, vec!["http://example.com/form"]);
+
+        assert_eq!(sent[1].method, Method::GET);
+        assert!(sent[1].body.is_empty());
+        assert!(!sent[1].headers.contains_key(hyper::header::CONTENT_TYPE));
+        // Same origin, so the credentials are kept
+        assert!(sent[1].headers.contains_key(hyper::header::AUTHORIZATION));
+    }
+
+    #[test]
+    fn temporary_and_permanent_redirects_send_the_body_again() {
+        for status in [307, 308] {
+            let (followed, sent) = follow(
+                post("http://example.com/upload"),
+                FOLLOW_ALL,
+                &[(status, Some("https://other.example.com/upload")), (201, None)],
+            );
+
+            assert_eq!(followed.response.status(), 201);
+            assert_eq!(sent[1].method, Method::POST);
+            assert_eq!(sent[1].uri, "https://other.example.com/upload");
+            assert_eq!(sent[1].body, "data");
+            assert!(sent[1].headers.contains_key(hyper::header::CONTENT_TYPE));
+            // Another origin, so the credentials are not sent along
+            assert!(!sent[1].headers.contains_key(hyper::header::AUTHORIZATION));
+        }
+    }
+
+    #[test]
+    fn same_origin_policy_stops_at_another_origin() {
+        let policy = RedirectPolicy {
+            limit: 10,
+            same_origin: true,
+        };
+        let (followed, sent) = follow(
+            post("http://example.com/a"),
+            policy,
+            &[(307, Some("/b")), (307, Some("http://example.com:8080/c"))],
+        );
+
+        assert_eq!(sent.len(), 2);
+        assert_eq!(followed.response.status(), 307);
+        assert_eq!(followed.final_uri, "http://example.com/b");
+        assert_eq!(followed.redirect_chain, vec!["http://example.com/a"]);
+    }
+
+    #[test]
+    fn stops_at_the_redirect_limit() {
+        let policy = RedirectPolicy {
+            limit: 3,
+            same_origin: false,
+        };
+        let (followed, sent) = follow(
+            post("http://example.com/loop"),
+            policy,
+            &[(302, Some("/loop"))],
+        );
+
+        assert_eq!(sent.len(), 4);
+        assert_eq!(followed.redirect_chain.len(), 3);
+        assert_eq!(followed.response.status(), 302);
+    }
+}
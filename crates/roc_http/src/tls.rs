@@ -0,0 +1,167 @@
+//! The TLS configuration of the http client: which servers are trusted and the certificate to present to them.
+use roc_std::{RocList, RocRefcounted, RocStr};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::sync::Arc;
+
+/// The options of `Http.configure_tls!`, empty strings mean no client certificate.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[repr(C)]
+pub struct TlsOptionsFromHost {
+    pub client_cert_chain: RocStr,
+    pub client_private_key: RocStr,
+    /// PEM encoded certificates that are trusted along with the native root certificates.
+    pub root_certs: RocList<RocStr>,
+    pub insecure_skip_verify: bool,
+}
+
+impl RocRefcounted for TlsOptionsFromHost {
+    fn inc(&mut self) {
+        self.client_cert_chain.inc();
+        self.client_private_key.inc();
+        self.root_certs.inc();
+    }
+    fn dec(&mut self) {
+        self.client_cert_chain.dec();
+        self.client_private_key.dec();
+        self.root_certs.dec();
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// The configuration that is used until `Http.configure_tls!` is called: only the native root certificates are trusted.
+pub fn default_client_config() -> Result<ClientConfig, String> {
+    client_config(&TlsOptionsFromHost {
+        client_cert_chain: RocStr::empty(),
+        client_private_key: RocStr::empty(),
+        root_certs: RocList::empty(),
+        insecure_skip_verify: false,
+    })
+}
+
+pub fn client_config(options: &TlsOptionsFromHost) -> Result<ClientConfig, String> {
+    let builder = ClientConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?;
+
+    let builder = if options.insecure_skip_verify {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(crypto_provider())))
+    } else {
+        builder.with_root_certificates(root_cert_store(&options.root_certs)?)
+    };
+
+    if options.client_cert_chain.is_empty() && options.client_private_key.is_empty() {
+        return Ok(builder.with_no_client_auth());
+    }
+
+    let cert_chain = CertificateDer::pem_slice_iter(options.client_cert_chain.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Invalid client certificate: {}", err))?;
+
+    if cert_chain.is_empty() {
+        return Err("The client certificate chain has no PEM encoded certificates".to_string());
+    }
+
+    let private_key = PrivateKeyDer::from_pem_slice(options.client_private_key.as_bytes())
+        .map_err(|err| format!("Invalid client private key: {}", err))?;
+
+    builder
+        .with_client_auth_cert(cert_chain, private_key)
+        .map_err(|err| err.to_string())
+}
+
+/// The native root certificates and `extra_roots`. rustls-native-certs loads the certificates from
+/// `SSL_CERT_FILE` or `SSL_CERT_DIR` instead of the ones of the system when those are set.
+fn root_cert_store(extra_roots: &RocList<RocStr>) -> Result<RootCertStore, String> {
+    let mut roots = RootCertStore::empty();
+
+    let native = rustls_native_certs::load_native_certs();
+    roots.add_parsable_certificates(native.certs);
+
+    for pem in extra_roots.iter() {
+        let certs = CertificateDer::pem_slice_iter(pem.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("Invalid root certificate: {}", err))?;
+
+        if certs.is_empty() {
+            return Err("A root certificate has no PEM encoded certificates".to_string());
+        }
+
+        for cert in certs {
+            roots
+                .add(cert)
+                .map_err(|err| format!("Invalid root certificate: {}", err))?;
+        }
+    }
+
+    if roots.is_empty() {
+        let errors: Vec<String> = native.errors.iter().map(ToString::to_string).collect();
+        return Err(format!(
+            "Failed to load the native root certificates: {}",
+            errors.join(", ")
+        ));
+    }
+
+    Ok(roots)
+}
+
+/// Accepts any certificate, for `insecure_skip_verify`.
+/// The signatures of the handshake are still checked, so the connection is encrypted, but anyone can pretend to be the server.
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -8,11 +8,13 @@ pub enum IOErrTag {
     BrokenPipe = 1,
     EndOfFile = 2,
     Interrupted = 3,
-    NotFound = 4,
-    Other = 5,
-    OutOfMemory = 6,
-    PermissionDenied = 7,
-    Unsupported = 8,
+    NotADirectory = 4,
+    NotFound = 5,
+    Other = 6,
+    OutOfMemory = 7,
+    PermissionDenied = 8,
+    Unsupported = 9,
+    WouldBlock = 10,
 }
 
 impl core::fmt::Debug for IOErrTag {
@@ -22,11 +24,13 @@ impl core::fmt::Debug for IOErrTag {
             Self::BrokenPipe => f.write_str("IOErrTag::BrokenPipe"),
             Self::EndOfFile => f.write_str("IOErrTag::EndOfFile"),
             Self::Interrupted => f.write_str("IOErrTag::Interrupted"),
+            Self::NotADirectory => f.write_str("IOErrTag::NotADirectory"),
             Self::NotFound => f.write_str("IOErrTag::NotFound"),
             Self::Other => f.write_str("IOErrTag::Other"),
             Self::OutOfMemory => f.write_str("IOErrTag::OutOfMemory"),
             Self::PermissionDenied => f.write_str("IOErrTag::PermissionDenied"),
             Self::Unsupported => f.write_str("IOErrTag::Unsupported"),
+            Self::WouldBlock => f.write_str("IOErrTag::WouldBlock"),
         }
     }
 }
@@ -68,6 +72,18 @@ impl From<std::io::Error> for IOErr {
             }
         };
 
+        // The message is kept for the stdio modules, they report these as `Other`.
+        let with_msg = |tag: IOErrTag| -> IOErr {
+            IOErr {
+                tag,
+                msg: format!("{}", e).as_str().into(),
+            }
+        };
+
+        if is_not_a_directory(&e) {
+            return with_msg(IOErrTag::NotADirectory);
+        }
+
         match e.kind() {
             std::io::ErrorKind::NotFound => with_empty_msg(IOErrTag::NotFound),
             std::io::ErrorKind::PermissionDenied => with_empty_msg(IOErrTag::PermissionDenied),
@@ -76,7 +92,18 @@ impl From<std::io::Error> for IOErr {
             std::io::ErrorKind::Interrupted => with_empty_msg(IOErrTag::Interrupted),
             std::io::ErrorKind::Unsupported => with_empty_msg(IOErrTag::Unsupported),
             std::io::ErrorKind::OutOfMemory => with_empty_msg(IOErrTag::OutOfMemory),
+            std::io::ErrorKind::WouldBlock => with_msg(IOErrTag::WouldBlock),
             _ => other(),
         }
     }
 }
+
+/// `ErrorKind::NotADirectory` is not stable in our rust version, so this checks the os error.
+fn is_not_a_directory(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    return e.raw_os_error() == Some(libc::ENOTDIR);
+
+    // ERROR_DIRECTORY: "The directory name is invalid."
+    #[cfg(windows)]
+    return e.raw_os_error() == Some(267);
+}
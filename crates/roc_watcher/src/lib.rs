@@ -0,0 +1,400 @@
+//! This crate provides common functionality for Roc to watch the filesystem for changes using [notify](https://docs.rs/notify),
+//! which uses inotify on Linux.
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+use roc_io_error::{IOErr, IOErrTag};
+use roc_std::{RocBox, RocList, RocResult, RocStr};
+use roc_std_heap::ThreadSafeRefcountedResourceHeap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+pub struct Watcher {
+    // Dropping this stops the watches, so it lives as long as the receiver
+    watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// An error received after some events were already collected for a batch,
+    /// it is returned on the next call so the events before it are not lost.
+    pending_err: Option<notify::Error>,
+}
+
+pub fn heap() -> &'static ThreadSafeRefcountedResourceHeap<Watcher> {
+    static WATCHER_HEAP: OnceLock<ThreadSafeRefcountedResourceHeap<Watcher>> = OnceLock::new();
+    WATCHER_HEAP.get_or_init(|| {
+        let default_max_watchers = 1024;
+        let max_watchers = std::env::var("ROC_BASIC_CLI_MAX_WATCHERS")
+            .map(|v| v.parse().unwrap_or(default_max_watchers))
+            .unwrap_or(default_max_watchers);
+        ThreadSafeRefcountedResourceHeap::new(max_watchers)
+            .expect("Failed to allocate mmap for watcher handle references.")
+    })
+}
+
+/// The variants are sorted alphabetically to match the tag ids roc uses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum InternalWatchEventKind {
+    Created = 0,
+    Modified = 1,
+    Removed = 2,
+    Renamed = 3,
+}
+
+/// `from` is only used for `Renamed`, `path` is the new path in that case.
+/// The fields are sorted by alignment and then alphabetically to match the layout of the roc record.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct InternalWatchEvent {
+    pub from: RocList<u8>,
+    pub path: RocList<u8>,
+    pub kind: InternalWatchEventKind,
+}
+
+impl roc_std::RocRefcounted for InternalWatchEvent {
+    fn inc(&mut self) {
+        self.from.inc();
+        self.path.inc();
+    }
+    fn dec(&mut self) {
+        self.from.dec();
+        self.path.dec();
+    }
+    fn is_refcounted() -> bool {
+        true
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum WatchEvent {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed(PathBuf, PathBuf),
+}
+
+pub fn watcher_new() -> RocResult<RocBox<()>, IOErr> {
+    let (sender, events) = mpsc::channel();
+
+    let watcher = match notify::recommended_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(err) => return RocResult::err(notify_err_to_io_err(err)),
+    };
+
+    let alloc_result = heap().alloc_for(Watcher {
+        watcher,
+        events,
+        pending_err: None,
+    });
+    match alloc_result {
+        Ok(out) => RocResult::ok(out),
+        Err(err) => RocResult::err(err.into()),
+    }
+}
+
+pub fn watcher_watch(data: RocBox<()>, roc_path: &RocList<u8>, recursive: bool) -> RocResult<(), IOErr> {
+    let watcher: &mut Watcher = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+
+    match watcher
+        .watcher
+        .watch(&roc_file::path_from_roc_path(roc_path), mode)
+    {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(notify_err_to_io_err(err)),
+    }
+}
+
+pub fn watcher_unwatch(data: RocBox<()>, roc_path: &RocList<u8>) -> RocResult<(), IOErr> {
+    let watcher: &mut Watcher = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    match watcher
+        .watcher
+        .unwatch(&roc_file::path_from_roc_path(roc_path))
+    {
+        Ok(()) => RocResult::ok(()),
+        Err(err) => RocResult::err(notify_err_to_io_err(err)),
+    }
+}
+
+/// Waits up to `timeout_ms` for a first event, then keeps collecting events until none arrive for `debounce_ms`
+/// or the batch reaches its maximum duration. Returns an empty list on timeout.
+pub fn watcher_next(
+    data: RocBox<()>,
+    timeout_ms: u64,
+    debounce_ms: u64,
+) -> RocResult<RocList<InternalWatchEvent>, IOErr> {
+    let watcher: &mut Watcher = ThreadSafeRefcountedResourceHeap::box_to_resource(data);
+
+    if let Some(err) = watcher.pending_err.take() {
+        return RocResult::err(notify_err_to_io_err(err));
+    }
+
+    let mut raw_events = Vec::new();
+
+    match watcher
+        .events
+        .recv_timeout(Duration::from_millis(timeout_ms))
+    {
+        Ok(Ok(event)) => raw_events.push(event),
+        Ok(Err(err)) => return RocResult::err(notify_err_to_io_err(err)),
+        Err(RecvTimeoutError::Timeout) => return RocResult::ok(RocList::empty()),
+        Err(RecvTimeoutError::Disconnected) => {
+            return RocResult::err(IOErr {
+                msg: "The watcher stopped unexpectedly".into(),
+                tag: IOErrTag::Other,
+            })
+        }
+    }
+
+    // Editors often save a file with several writes or a write and a rename,
+    // wait until things are quiet so those end up in a single batch.
+    // Under continuous writes it never gets quiet, the deadline makes sure the batch is returned anyway.
+    let debounce = Duration::from_millis(debounce_ms);
+    let deadline = Instant::now().checked_add(max_batch_duration(debounce));
+
+    loop {
+        let wait = match deadline {
+            Some(deadline) => debounce.min(deadline.saturating_duration_since(Instant::now())),
+            None => debounce,
+        };
+
+        if wait.is_zero() {
+            break;
+        }
+
+        match watcher.events.recv_timeout(wait) {
+            Ok(Ok(event)) => raw_events.push(event),
+            Ok(Err(err)) => {
+                watcher.pending_err = Some(err);
+                break;
+            }
+            // A disconnect is reported by the next call
+            Err(_) => break,
+        }
+    }
+
+    let events = coalesce(raw_events).into_iter().map(|event| {
+        let (kind, from, path) = match event {
+            WatchEvent::Created(path) => (InternalWatchEventKind::Created, None, path),
+            WatchEvent::Modified(path) => (InternalWatchEventKind::Modified, None, path),
+            WatchEvent::Removed(path) => (InternalWatchEventKind::Removed, None, path),
+            WatchEvent::Renamed(from, to) => (InternalWatchEventKind::Renamed, Some(from), to),
+        };
+
+        InternalWatchEvent {
+            from: from.map_or_else(RocList::empty, |from| {
+                roc_file::os_str_to_roc_path(from.as_os_str())
+            }),
+            path: roc_file::os_str_to_roc_path(path.as_os_str()),
+            kind,
+        }
+    });
+
+    RocResult::ok(RocList::from_iter(events))
+}
+
+/// How long a batch keeps collecting events after the first one: ten debounce windows, but at least a second.
+fn max_batch_duration(debounce: Duration) -> Duration {
+    debounce.saturating_mul(10).max(Duration::from_secs(1))
+}
+
+/// Turns the raw events into Created, Modified, Removed and Renamed, without duplicates.
+fn coalesce(raw_events: Vec<Event>) -> Vec<WatchEvent> {
+    // On Linux a rename is reported as a `From` and a `To` event, followed by a `Both` event with the same tracker.
+    let paired_trackers: HashSet<usize> = raw_events
+        .iter()
+        .filter(|event| {
+            event.kind == EventKind::Modify(ModifyKind::Name(RenameMode::Both))
+        })
+        .filter_map(Event::tracker)
+        .collect();
+
+    let mut events = Vec::new();
+
+    for event in raw_events {
+        match event.kind {
+            EventKind::Create(_) => {
+                events.extend(event.paths.into_iter().map(WatchEvent::Created));
+            }
+            EventKind::Remove(_) => {
+                events.extend(event.paths.into_iter().map(WatchEvent::Removed));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                if let [from, to] = event.paths.as_slice() {
+                    events.push(WatchEvent::Renamed(from.clone(), to.clone()));
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(mode)) => {
+                if event
+                    .tracker()
+                    .is_some_and(|tracker| paired_trackers.contains(&tracker))
+                {
+                    continue;
+                }
+
+                // Only one side of the rename is watched, or the platform does not say which side this is
+                events.extend(event.paths.into_iter().map(|path| match mode {
+                    RenameMode::From => WatchEvent::Removed(path),
+                    RenameMode::To => WatchEvent::Created(path),
+                    _ => created_or_removed(path),
+                }));
+            }
+            EventKind::Access(_) => {}
+            EventKind::Modify(_) | EventKind::Any | EventKind::Other => {
+                events.extend(event.paths.into_iter().map(WatchEvent::Modified));
+            }
+        }
+    }
+
+    let mut seen: HashSet<WatchEvent> = HashSet::with_capacity(events.len());
+    let mut unique: Vec<WatchEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        let already_reported = match &event {
+            // A new file is also written to, that is part of it being created
+            WatchEvent::Modified(path) => {
+                seen.contains(&event) || seen.contains(&WatchEvent::Created(path.clone()))
+            }
+            _ => seen.contains(&event),
+        };
+
+        if !already_reported {
+            seen.insert(event.clone());
+            unique.push(event);
+        }
+    }
+
+    unique
+}
+
+fn created_or_removed(path: PathBuf) -> WatchEvent {
+    if path.symlink_metadata().is_ok() {
+        WatchEvent::Created(path)
+    } else {
+        WatchEvent::Removed(path)
+    }
+}
+
+fn notify_err_to_io_err(err: notify::Error) -> IOErr {
+    match err.kind {
+        notify::ErrorKind::Io(io_err) => io_err.into(),
+        notify::ErrorKind::PathNotFound | notify::ErrorKind::WatchNotFound => IOErr {
+            msg: RocStr::empty(),
+            tag: IOErrTag::NotFound,
+        },
+        _ => IOErr {
+            msg: err.to_string().as_str().into(),
+            tag: IOErrTag::Other,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&str], tracker: Option<usize>) -> Event {
+        let mut event = Event::new(kind);
+        for path in paths {
+            event = event.add_path(PathBuf::from(path));
+        }
+        match tracker {
+            Some(tracker) => event.set_tracker(tracker),
+            None => event,
+        }
+    }
+
+    fn rename(mode: RenameMode) -> EventKind {
+        EventKind::Modify(ModifyKind::Name(mode))
+    }
+
+    #[test]
+    fn rename_pairs_become_one_event() {
+        let events = coalesce(vec![
+            event(rename(RenameMode::From), &["/w/old"], Some(7)),
+            event(rename(RenameMode::To), &["/w/new"], Some(7)),
+            event(rename(RenameMode::Both), &["/w/old", "/w/new"], Some(7)),
+        ]);
+
+        assert_eq!(
+            events,
+            vec![WatchEvent::Renamed(PathBuf::from("/w/old"), PathBuf::from("/w/new"))]
+        );
+    }
+
+    #[test]
+    fn rename_with_one_side_seen() {
+        let events = coalesce(vec![
+            event(rename(RenameMode::From), &["/w/moved_out"], Some(1)),
+            event(rename(RenameMode::To), &["/w/moved_in"], Some(2)),
+        ]);
+
+        assert_eq!(
+            events,
+            vec![
+                WatchEvent::Removed(PathBuf::from("/w/moved_out")),
+                WatchEvent::Created(PathBuf::from("/w/moved_in")),
+            ]
+        );
+    }
+
+    #[test]
+    fn modified_after_created_is_dropped() {
+        let modified = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        let events = coalesce(vec![
+            event(EventKind::Create(CreateKind::File), &["/w/new"], None),
+            event(modified, &["/w/new"], None),
+            event(modified, &["/w/other"], None),
+            event(modified, &["/w/other"], None),
+            event(EventKind::Remove(RemoveKind::File), &["/w/other"], None),
+        ]);
+
+        assert_eq!(
+            events,
+            vec![
+                WatchEvent::Created(PathBuf::from("/w/new")),
+                WatchEvent::Modified(PathBuf::from("/w/other")),
+                WatchEvent::Removed(PathBuf::from("/w/other")),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_times_out_then_returns_the_debounced_batch() {
+        let dir = std::env::temp_dir().join(format!("roc_watcher_next_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let watcher = Result::from(watcher_new()).unwrap();
+        let roc_dir = roc_file::os_str_to_roc_path(dir.as_os_str());
+        Result::from(watcher_watch(watcher.clone(), &roc_dir, false)).unwrap();
+
+        let started = Instant::now();
+        let nothing = Result::from(watcher_next(watcher.clone(), 50, 20)).unwrap();
+        assert!(nothing.is_empty());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        // Several writes in a row end up in one batch, as a single Created event
+        let file = dir.join("file.txt");
+        std::fs::write(&file, b"one").unwrap();
+        std::fs::write(&file, b"two").unwrap();
+
+        let events = Result::from(watcher_next(watcher, 5_000, 200)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, InternalWatchEventKind::Created);
+        assert_eq!(
+            events[0].path.as_slice(),
+            roc_file::os_str_to_roc_path(file.as_os_str()).as_slice()
+        );
+    }
+}
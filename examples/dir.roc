@@ -33,6 +33,47 @@ main! = |_args|
     # Check the contents of the directory
     expect Set.from_list(paths_as_str) == Set.from_list(["nested-dir/a", "nested-dir/child"])
 
+    # Copy a directory and everything inside it
+    Dir.copy_all!(
+        "nested-dir",
+        "nested-dir-copy",
+        { overwrite: FailIfExists, preserve_mode: Bool.true, preserve_times: Bool.false, sym_links: CopyLinks },
+    )?
+
+    copy_is_dir = File.is_dir!("nested-dir-copy/a/b/c")?
+    expect copy_is_dir
+
+    Dir.delete_all!("nested-dir-copy")?
+
+    # Walk over a directory recursively
+    File.write_utf8!("ignored.txt", "nested-dir/.gitignore")?
+    File.write_utf8!("", "nested-dir/ignored.txt")?
+    File.write_utf8!("", "nested-dir/a/b/file.txt")?
+
+    walker = Dir.walk!("nested-dir", { max_depth: Unlimited, follow_sym_links: Bool.false, skip_hidden: Bool.true, respect_gitignore: Bool.true })?
+    walked = walk_all!(walker, [])?
+    walked_as_str = List.map(walked, |entry| Path.display(entry.path))
+
+    expect Set.from_list(walked_as_str) == Set.from_list(["nested-dir/a", "nested-dir/a/b", "nested-dir/a/b/c", "nested-dir/a/b/file.txt", "nested-dir/child"])
+    expect List.any(walked, |entry| entry.type == IsFile and entry.depth == 3)
+
+    # Limit how deep the walk goes, without skipping anything
+    shallow_walker = Dir.walk!("nested-dir", { max_depth: Limit(1), follow_sym_links: Bool.false, skip_hidden: Bool.false, respect_gitignore: Bool.false })?
+    shallow = walk_all!(shallow_walker, [])?
+    shallow_as_str = List.map(shallow, |entry| Path.display(entry.path))
+
+    expect Set.from_list(shallow_as_str) == Set.from_list(["nested-dir/a", "nested-dir/child", "nested-dir/.gitignore", "nested-dir/ignored.txt"])
+
+    # Check the space on the filesystem
+    disk = Dir.disk_usage!("nested-dir")?
+    expect disk.total >= disk.free and disk.free >= disk.available
+    expect !Str.is_empty(disk.fs_type)
+
+    # Add up the size of a directory
+    File.write_utf8!("twelve bytes", "nested-dir/child/twelve.txt")?
+    size = Dir.size_recursive!("nested-dir/child")?
+    expect size.apparent >= 12
+
     # Delete an empty directory
     Dir.delete_empty!("empty-dir")?
 
@@ -40,3 +81,9 @@ main! = |_args|
     Dir.delete_all!("nested-dir")?
 
     Stdout.line!("Success!")
+
+walk_all! : Dir.Walker, List Dir.WalkEntry => Result (List Dir.WalkEntry) [DirErr Dir.IOErr]
+walk_all! = |walker, entries|
+    when Dir.walk_next!(walker)? is
+        [] -> Ok(entries)
+        batch -> walk_all!(walker, List.concat(entries, batch))
@@ -5,6 +5,7 @@ app [main!] {
 
 import pf.Http
 import pf.Stdout
+import pf.File
 import json.Json
 import pf.Arg exposing [Arg]
 
@@ -21,6 +22,15 @@ import pf.Arg exposing [Arg]
 main! : List Arg => Result {} _
 main! = |_args|
 
+    # Keep connections open for 30 seconds so the requests below can reuse them
+    Http.configure_pool!({ idle_timeout_ms: TimeoutMilliseconds(30_000), max_idle_per_host: 8 })
+
+    # Trust the certificates of the system, certificates of a private CA can be added to root_certs
+    Http.configure_tls!({ root_certs: [], client_cert: NoClientCert, verify: VerifyCertificates })?
+
+    invalid_root = Http.configure_tls!({ root_certs: ["not a certificate"], client_cert: NoClientCert, verify: VerifyCertificates })
+    expect invalid_root == Err(HttpErr(TlsError("A root certificate has no PEM encoded certificates")))
+
     # # HTTP GET a String
     #   ----------------
 
@@ -30,6 +40,89 @@ main! = |_args|
 
     Stdout.line!("I received '${hello_str}' from the server.\n")?
 
+    # # Downloading to a file
+    #   ---------------------
+
+    { status } = Http.download!({ Http.default_request & uri: "http://localhost:9000/utf8test" }, "http-download.txt")?
+    downloaded = File.read_utf8!("http-download.txt")?
+    File.delete!("http-download.txt")?
+
+    Stdout.line!("Downloaded '${downloaded}' with status ${Num.to_str(status)}.\n")?
+
+    # # Receiving the body in chunks
+    #   ----------------------------
+
+    { body } = Http.send_streaming!({ Http.default_request & uri: "http://localhost:9000/utf8test" })?
+    received = receive_all!(body, [])?
+
+    Stdout.line!("Received ${Num.to_str(List.len(received))} bytes in chunks.\n")?
+
+    # # Uploading a file
+    #   ----------------
+
+    File.write_utf8!("Uploaded from a file", "http-upload.txt")?
+    upload_response = Http.send_file!({ Http.default_request & method: POST, uri: "http://localhost:9000/echo" }, "http-upload.txt")?
+    File.delete!("http-upload.txt")?
+
+    echoed = Str.from_utf8(upload_response.body)?
+    received_with =
+        List.find_first(upload_response.headers, |h| h.name == "x-received-with")
+        |> Result.map_ok(.value)
+        |> Result.with_default("")
+
+    Stdout.line!("The server received '${echoed}' with ${received_with}.\n")?
+
+    # # Following redirects
+    #   -------------------
+
+    not_followed = Http.send!({ Http.default_request & uri: "http://localhost:9000/redirect" })?
+    Stdout.line!("Without following redirects the status is ${Num.to_str(not_followed.status)}.\n")?
+    # Plain http is always HTTP/1.1, https uses HTTP/2 if the server supports it
+    expect not_followed.version == Http11
+
+    followed = Http.send!({ Http.default_request & uri: "http://localhost:9000/redirect", redirect_policy: FollowSameOriginUpTo(5) })?
+    followed_body = Str.from_utf8(followed.body)?
+    Stdout.line!("Followed ${Str.join_with(followed.redirect_chain, ", ")} to ${followed.final_uri}: '${followed_body}'.\n")?
+
+    resent = Http.send!({ Http.default_request & method: POST, uri: "http://localhost:9000/redirect-307", body: Str.to_utf8("sent twice"), redirect_policy: FollowUpTo(1) })?
+    resent_body = Str.from_utf8(resent.body)?
+    Stdout.line!("A 307 redirect sent '${resent_body}' again.\n")?
+
+    # # Receiving a compressed body
+    #   --------------------------
+
+    compressed = Http.send!({ Http.default_request & uri: "http://localhost:9000/gzip", accept_encoding: [Zstd, Gzip] })?
+    decompressed = Str.from_utf8(compressed.body)?
+    content_encoding = List.find_first(compressed.headers, |h| h.name == "content-encoding") |> Result.map_ok(|h| h.value) |> Result.with_default("none")
+    Stdout.line!("Decoded the ${content_encoding} body: '${decompressed}'.\n")?
+
+    # # Sending several requests at the same time
+    #   -----------------------------------------
+
+    batch = [
+        { Http.default_request & uri: "http://localhost:9000/utf8test" },
+        { Http.default_request & uri: "http://localhost:9001/nothing-here" },
+        { Http.default_request & uri: "http://localhost:9000/request-target" },
+    ]
+    batch_results = Http.send_all!(batch, { max_concurrency: 2, timeout_ms: TimeoutMilliseconds(5000) })
+    batch_summary =
+        batch_results
+        |> List.map(|result|
+            when result is
+                Ok(batch_response) -> Num.to_str(batch_response.status)
+                Err(ConnectionRefused) -> "ConnectionRefused"
+                Err(_) -> "other error")
+        |> Str.join_with(", ")
+    Stdout.line!("Results of send_all!: ${batch_summary}.\n")?
+
+    # # Sending a request through a proxy
+    #   --------------------------------
+
+    # The test server pretends to be a proxy here, it shows the uri it was asked for
+    proxied = Http.send!({ Http.default_request & uri: "http://basic-cli.test/request-target", proxy: Via("localhost:9000") })?
+    proxied_target = Str.from_utf8(proxied.body)?
+    Stdout.line!("The proxy was asked for ${proxied_target}.\n")?
+
     # # Getting json
     #   ------------
 
@@ -51,6 +144,9 @@ main! = |_args|
             uri: "https://www.example.com",
             body: [],
             timeout_ms: TimeoutMilliseconds(5000),
+            redirect_policy: FollowUpTo(5),
+            proxy: FromEnv,
+            accept_encoding: [Gzip, Br],
         },
     )?
 
@@ -70,4 +166,21 @@ main! = |_args|
     body_str_2 = (Str.from_utf8(response_2.body))?
 
     # Same as above
-    Stdout.line!("Response body 2:\n\t${body_str_2}.\n")
+    Stdout.line!("Response body 2:\n\t${body_str_2}.\n")?
+
+    # # Handling errors
+    #   ---------------
+
+    # Nothing is listening on port 9001
+    when Http.send!({ Http.default_request & uri: "http://localhost:9001" }) is
+        Err(HttpErr(ConnectionRefused)) -> Stdout.line!("Port 9001: ConnectionRefused")
+        Err(HttpErr(err)) -> Stdout.line!("Port 9001: unexpected error ${Inspect.to_str(err)}")
+        Ok(_) -> Stdout.line!("Port 9001: unexpected response")
+
+receive_all! : Http.ResponseStream, List U8 => Result (List U8) [HttpErr Http.HttpErr]
+receive_all! = |body, received|
+    chunk = Http.next_chunk!(body)?
+    if List.is_empty(chunk) then
+        Ok(received)
+    else
+        receive_all!(body, List.concat(received, chunk))
@@ -0,0 +1,45 @@
+app [main!] { pf: platform "../platform/main.roc" }
+
+# To run this example: check the README.md in this folder
+
+# Demo of basic-cli Watcher functions
+
+import pf.Stdout
+import pf.Watcher
+import pf.Dir
+import pf.File
+import pf.Path
+import pf.Arg exposing [Arg]
+
+main! : List Arg => Result {} _
+main! = |_args|
+    Dir.create_all!("watched-dir/nested")?
+
+    watcher = Watcher.new!({})?
+    Watcher.watch!(watcher, "watched-dir", Recursive)?
+
+    # Changes are queued up, so they are not missed between calls to Watcher.next!
+    File.write_utf8!("first version", "watched-dir/nested/notes.txt")?
+
+    created_events = Watcher.next!(watcher, { timeout_ms: 5000, debounce_ms: 200 })?
+    Stdout.line!("Creating a file was reported: ${Inspect.to_str(List.any(created_events, |event| is_about(event, "notes.txt")))}")?
+
+    File.rename!("watched-dir/nested/notes.txt", "watched-dir/nested/renamed.txt")?
+
+    renamed_events = Watcher.next!(watcher, { timeout_ms: 5000, debounce_ms: 200 })?
+    Stdout.line!("Renaming a file was reported: ${Inspect.to_str(List.any(renamed_events, |event| is_about(event, "renamed.txt")))}")?
+
+    # Nothing changed, so this returns an empty list after the timeout
+    quiet_events = Watcher.next!(watcher, { timeout_ms: 100, debounce_ms: 0 })?
+    Stdout.line!("Events while nothing changed: ${Num.to_str(List.len(quiet_events))}")?
+
+    Watcher.unwatch!(watcher, "watched-dir")?
+    Dir.delete_all!("watched-dir")?
+
+    Stdout.line!("Success!")
+
+is_about : Watcher.Event, Str -> Bool
+is_about = |event, file_name|
+    when event is
+        Created(path) | Modified(path) | Removed(path) -> Str.ends_with(Path.display(path), file_name)
+        Renamed({ to }) -> Str.ends_with(Path.display(to), file_name)
@@ -1,14 +1,27 @@
 module [
     IOErr,
+    Walker,
+    WalkOptions,
+    WalkEntry,
+    DiskUsage,
     list!,
     create!,
     create_all!,
     delete_empty!,
     delete_all!,
+    copy_all!,
+    walk!,
+    walk_next!,
+    create_temp!,
+    keep_temp!,
+    disk_usage!,
+    size_recursive!,
 ]
 
 import Path exposing [Path]
 import InternalIOErr
+import InternalPath
+import Host
 
 ## Tag union of possible errors when reading and writing a file or directory.
 ##
@@ -70,3 +83,148 @@ create! = |path|
 create_all! : Str => Result {} [DirErr IOErr]
 create_all! = |path|
     Path.create_all!(Path.from_str(path))
+
+## Recursively copies a directory and everything inside it.
+##
+## ```
+## Dir.copy_all!("assets", "dist/assets", { overwrite: Overwrite, preserve_mode: Bool.true, preserve_times: Bool.false, sym_links: CopyLinks })?
+## ```
+##
+## See [Path.DirCopyOptions] for what the options do.
+##
+## > [Path.copy_all!] does the same thing, except it takes a [Path] instead of a [Str].
+copy_all! : Str, Str, Path.DirCopyOptions => Result {} [DirErr IOErr]
+copy_all! = |from, to, options|
+    Path.copy_all!(Path.from_str(from), Path.from_str(to), options)
+
+## A recursive walk over a directory, created with [walk!].
+Walker := Host.DirWalker
+
+## Options for [walk!].
+##
+## - `max_depth`: `Limit(1)` only returns the direct children of the directory, `Limit(2)` also their children, and so on.
+## - `follow_sym_links`: walk into directories that symbolic links point to. Link loops are reported as an error.
+## - `skip_hidden`: leave out files and directories whose name starts with a `.`.
+## - `respect_gitignore`: leave out everything matched by `.gitignore`, `.ignore` and `.git/info/exclude` files.
+WalkOptions : {
+    max_depth : [Unlimited, Limit U64],
+    follow_sym_links : Bool,
+    skip_hidden : Bool,
+    respect_gitignore : Bool,
+}
+
+## A file, directory or link found by [walk_next!].
+##
+## The `type` is read from the directory listing itself, so no extra system call is needed.
+## It is the type of the link itself, unless `follow_sym_links` is enabled.
+WalkEntry : {
+    path : Path,
+    type : [IsFile, IsDir, IsSymLink, IsFifo, IsSocket, IsBlockDevice, IsCharDevice, IsOther],
+    depth : U64,
+}
+
+## Starts a recursive walk over everything inside a directory.
+## The entries are read with [walk_next!].
+##
+## ```
+## walker = Dir.walk!("src", { max_depth: Unlimited, follow_sym_links: Bool.false, skip_hidden: Bool.true, respect_gitignore: Bool.true })?
+## ```
+##
+## A path that is not a directory fails with `NotADirectory`.
+##
+## This uses the [ignore crate](https://docs.rs/ignore/latest/ignore/struct.WalkBuilder.html), which is also used by ripgrep.
+walk! : Str, WalkOptions => Result Walker [DirErr IOErr]
+walk! = |path_str, { max_depth, follow_sym_links, skip_hidden, respect_gitignore }|
+    # U64 max means no limit
+    max_depth_u64 =
+        when max_depth is
+            Unlimited -> Num.max_u64
+            Limit(depth) -> depth
+
+    Host.dir_walk!(InternalPath.to_bytes(Path.from_str(path_str)), max_depth_u64, follow_sym_links, skip_hidden, respect_gitignore)
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
+    |> Result.map_ok(@Walker)
+
+## Returns the next batch of entries from a [Walker].
+## Batches contain up to 1024 entries, an empty list means the walk is done.
+##
+## ```
+## count_files! = |walker, count|
+##     when Dir.walk_next!(walker)? is
+##         [] -> Ok(count)
+##         entries -> count_files!(walker, count + List.count_if(entries, |entry| entry.type == IsFile))
+## ```
+##
+## When a directory can not be read, the entries found before it are returned first
+## and the error is returned by the next call. The walk can continue after an error.
+walk_next! : Walker => Result (List WalkEntry) [DirErr IOErr]
+walk_next! = |@Walker(walker)|
+    Host.dir_walk_next!(walker)
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
+    |> Result.map_ok(|entries|
+        List.map(entries, |entry|
+            {
+                path: InternalPath.from_os_bytes(entry.path),
+                type: entry.file_type,
+                depth: entry.depth,
+            }))
+
+## Creates a new, empty directory with a unique name in the [temp dir](Env#temp_dir!) and returns its path.
+##
+## ```
+## build_dir = Dir.create_temp!({ prefix: "build-", suffix: "" })?
+## ```
+##
+## The name is `prefix`, 16 random characters and `suffix`. Like `mkdtemp`, creating the directory fails
+## instead of reusing an existing one, and only the current user has access to it.
+##
+## The directory and everything inside it is deleted when the program exits, also when it crashes.
+## Use [keep_temp!] to keep it around.
+create_temp! : { prefix : Str, suffix : Str } => Result Path [DirErr IOErr]
+create_temp! = |{ prefix, suffix }|
+    Host.temp_dir_create!(prefix, suffix)
+    |> Result.map_ok(InternalPath.from_os_bytes)
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
+
+## Keeps a directory created with [create_temp!], so it is not deleted when the program exits.
+keep_temp! : Path => {}
+keep_temp! = |path|
+    Host.temp_keep!(InternalPath.to_bytes(path))
+
+## Space on the filesystem a directory is on, in bytes, see [disk_usage!].
+##
+## - `free` includes the space that is reserved for root, `available` is what other users can use.
+## - `fs_type` is the name of the filesystem, like `ext4`, `btrfs`, `tmpfs` or `apfs`.
+DiskUsage : {
+    total : U64,
+    free : U64,
+    available : U64,
+    fs_type : Str,
+}
+
+## Returns the size and free space of the filesystem the path is on.
+##
+## ```
+## { available } = Dir.disk_usage!("build")?
+## if available < artifact_size then
+##     Err(NotEnoughSpace)
+## else
+##     build!({})
+## ```
+##
+## This uses [statvfs](https://man7.org/linux/man-pages/man3/statvfs.3.html).
+disk_usage! : Str => Result DiskUsage [DirErr IOErr]
+disk_usage! = |path_str|
+    Host.dir_disk_usage!(InternalPath.to_bytes(Path.from_str(path_str)))
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
+
+## Returns the size of a directory and everything inside it, like `du`.
+##
+## - `apparent` is the sum of the file sizes, like `du --apparent-size`.
+## - `allocated` is the space used on disk, this is larger for many small files and smaller for sparse or compressed files.
+##
+## Symbolic links are not followed and files with several hard links are counted once.
+size_recursive! : Str => Result { apparent : U64, allocated : U64 } [DirErr IOErr]
+size_recursive! = |path_str|
+    Host.dir_size_recursive!(InternalPath.to_bytes(Path.from_str(path_str)))
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
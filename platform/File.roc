@@ -1,11 +1,16 @@
 module [
     IOErr,
+    Metadata,
     Reader,
+    Lock,
+    MappedFile,
     write_utf8!,
     write_bytes!,
+    write_atomic!,
     write!,
     read_utf8!,
     read_bytes!,
+    read_into!,
     delete!,
     is_dir!,
     is_file!,
@@ -18,12 +23,36 @@ module [
     time_modified!,
     time_created!,
     rename!,
+    copy!,
+    create_temp!,
+    keep_temp!,
+    lock!,
+    try_lock!,
+    lock_shared!,
+    unlock!,
+    get_xattr!,
+    set_xattr!,
+    list_xattrs!,
+    remove_xattr!,
+    map_readonly!,
+    mapped_len,
+    mapped_slice!,
     type!,
+    metadata!,
     open_reader!,
     open_reader_with_capacity!,
     read_line!,
     hard_link!,
+    sym_link!,
+    read_link!,
+    canonicalize!,
     size_in_bytes!,
+    set_permissions!,
+    chown!,
+    set_times!,
+    set_len!,
+    truncate!,
+    create_fifo!,
 ]
 
 import Path exposing [Path]
@@ -36,6 +65,8 @@ import Utc exposing [Utc]
 ##
 ## **NotFound** - An entity was not found, often a file.
 ##
+## **NotADirectory** - A directory was expected, but the path is a file or something else.
+##
 ## **PermissionDenied** - The operation lacked the necessary privileges to complete.
 ##
 ## **BrokenPipe** - The operation failed because a pipe was closed.
@@ -48,9 +79,14 @@ import Utc exposing [Utc]
 ##
 ## **OutOfMemory** - An operation could not be completed, because it failed to allocate enough memory.
 ##
+## **WouldBlock** - The operation would have to wait, for example for a lock that is held by someone else.
+##
 ## **Other** - A custom error that does not fall under any other I/O error kind.
 IOErr : InternalIOErr.IOErr
 
+## Everything the filesystem knows about a file, see [Path.Metadata] for the fields.
+Metadata : Path.Metadata
+
 ## Write data to a file.
 ##
 ## First encode a `val` using a given `fmt` which implements the ability [Encode.EncoderFormatting](https://www.roc-lang.org/builtins/Encode#EncoderFormatting).
@@ -95,6 +131,22 @@ write_bytes! : List U8, Str => Result {} [FileWriteErr Path IOErr]
 write_bytes! = |bytes, path_str|
     Path.write_bytes!(bytes, Path.from_str(path_str))
 
+## Writes bytes to a file without ever leaving it half-written.
+##
+## ```
+## # Replaces the contents of `config.json`, even if the program crashes midway.
+## File.write_atomic!(Str.to_utf8("{}"), "config.json")?
+## ```
+##
+## The bytes are written to a temporary file next to the target, flushed to disk and
+## then renamed over the target. The permissions of an existing file are kept.
+## When the path is a symbolic link, the file it points to is replaced and the link stays in place.
+##
+## > [Path.write_atomic!] does the same thing, except it takes a [Path] instead of a [Str].
+write_atomic! : List U8, Str => Result {} [FileWriteErr Path IOErr]
+write_atomic! = |bytes, path_str|
+    Path.write_atomic!(bytes, Path.from_str(path_str))
+
 ## Writes a [Str] to a file, encoded as [UTF-8](https://en.wikipedia.org/wiki/UTF-8).
 ##
 ## ```
@@ -151,6 +203,23 @@ read_bytes! : Str => Result (List U8) [FileReadErr Path IOErr]
 read_bytes! = |path_str|
     Path.read_bytes!(Path.from_str(path_str))
 
+## Reads all the bytes in a file into `buffer`, replacing what it held before.
+##
+## ```
+## # Read many files of similar size with a single allocation.
+## buffer = File.read_into!("day1.bin", List.with_capacity(1_000_000))?
+## # ...use buffer...
+## next = File.read_into!("day2.bin", buffer)?
+## ```
+##
+## The capacity of `buffer` is reused when nothing else refers to the list, so pass it on instead of keeping a copy around.
+## The list grows once to fit the whole file if it is too small, instead of step by step while reading.
+##
+## > [Path.read_into!] does the same thing, except it takes a [Path] instead of a [Str].
+read_into! : Str, List U8 => Result (List U8) [FileReadErr Path IOErr]
+read_into! = |path_str, buffer|
+    Path.read_into!(Path.from_str(path_str), buffer)
+
 ## Reads a [Str] from a file containing [UTF-8](https://en.wikipedia.org/wiki/UTF-8)-encoded text.
 ##
 ## ```
@@ -181,6 +250,30 @@ hard_link! : Str, Str => Result {} [LinkErr IOErr]
 hard_link! = |path_str_original, path_str_link|
     Path.hard_link!(Path.from_str(path_str_original), Path.from_str(path_str_link))
 
+## Creates a new [symbolic link](https://en.wikipedia.org/wiki/Symbolic_link) on the filesystem.
+##
+## The link path will be a link pointing to the original path.
+## A relative original path is resolved relative to the directory of the link.
+##
+## > [Path.sym_link!] does the same thing, except it takes a [Path] instead of a [Str].
+sym_link! : Str, Str => Result {} [LinkErr IOErr]
+sym_link! = |path_str_original, path_str_link|
+    Path.sym_link!(Path.from_str(path_str_original), Path.from_str(path_str_link))
+
+## Returns the path a symbolic link points to.
+##
+## > [Path.read_link!] does the same thing, except it takes a [Path] instead of a [Str].
+read_link! : Str => Result Path [PathErr IOErr]
+read_link! = |path_str|
+    Path.read_link!(Path.from_str(path_str))
+
+## Returns the absolute path with `.` and `..` removed and all symbolic links resolved.
+##
+## > [Path.canonicalize!] does the same thing, except it takes a [Path] instead of a [Str].
+canonicalize! : Str => Result Path [PathErr IOErr]
+canonicalize! = |path_str|
+    Path.canonicalize!(Path.from_str(path_str))
+
 ## Returns True if the path exists on disk and is pointing at a directory.
 ## Returns False if the path exists and it is not a directory. If the path does not exist,
 ## this function will return `Err (PathErr PathDoesNotExist)`.
@@ -293,6 +386,41 @@ rename! = |from_str, to_str|
     Host.file_rename!(from_bytes, to_bytes)
     |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
 
+## Copies the contents of a file to another file, returns the number of bytes copied.
+##
+## ```
+## File.copy!("release.tar.gz", "backup/release.tar.gz", { overwrite: Overwrite, preserve_mode: Bool.true, preserve_times: Bool.true })?
+## ```
+##
+## The data is streamed instead of read into memory, so this also works for multi-GB files.
+## See [Path.CopyOptions] for what the options do.
+##
+## > [Path.copy!] does the same thing, except it takes a [Path] instead of a [Str].
+copy! : Str, Str, Path.CopyOptions => Result U64 [PathErr IOErr]
+copy! = |from_str, to_str, options|
+    Path.copy!(Path.from_str(from_str), Path.from_str(to_str), options)
+
+## Creates a new, empty file with a unique name in the [temp dir](Env#temp_dir!) and returns its path.
+##
+## ```
+## path = File.create_temp!({ prefix: "report-", suffix: ".csv" })?
+## ```
+##
+## The name is `prefix`, 16 random characters and `suffix`. The file is created atomically with `O_EXCL`,
+## so it is never shared with another program. Only the current user can read and write it.
+##
+## The file is deleted when the program exits, also when it crashes. Use [keep_temp!] to keep it around.
+create_temp! : { prefix : Str, suffix : Str } => Result Path [PathErr IOErr]
+create_temp! = |{ prefix, suffix }|
+    Host.temp_file_create!(prefix, suffix)
+    |> Result.map_ok(InternalPath.from_os_bytes)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Keeps a file created with [create_temp!], so it is not deleted when the program exits.
+keep_temp! : Path => {}
+keep_temp! = |path|
+    Host.temp_keep!(InternalPath.to_bytes(path))
+
 ## Return the type of the path if the path exists on disk.
 ## This uses [rust's std::path::is_symlink](https://doc.rust-lang.org/std/path/struct.Path.html#method.is_symlink).
 ##
@@ -301,6 +429,21 @@ type! : Str => Result [IsFile, IsDir, IsSymLink] [PathErr IOErr]
 type! = |path_str|
     Path.type!(Path.from_str(path_str))
 
+## Returns the size, timestamps, permissions, owner, inode and type of a file
+## using a single `stat` call.
+##
+## ```
+## info = File.metadata!("myfile.txt", FollowSymLinks)?
+## ```
+##
+## This is much faster than calling [size_in_bytes!], [time_modified!], [type!], ...
+## one after the other, since each of those looks up the file again.
+##
+## > [Path.metadata!] does the same thing, except it takes a [Path] instead of a [Str].
+metadata! : Str, [FollowSymLinks, NoFollowSymLinks] => Result Metadata [PathErr IOErr]
+metadata! = |path_str, follow|
+    Path.metadata!(Path.from_str(path_str), follow)
+
 Reader := { reader : Host.FileReader, path : Path }
 
 ## Try to open a `File.Reader` for buffered (= part by part) reading given a path string.
@@ -346,6 +489,151 @@ read_line! = |@Reader({ reader, path })|
     Host.file_read_line!(reader)
     |> Result.map_err(|err| FileReadErr(path, InternalIOErr.handle_err(err)))
 
+## A file mapped into memory with [map_readonly!].
+MappedFile := { mapped : Host.MappedFile, len : U64 }
+
+## Maps a file into memory without reading it, parts of it can then be read with [mapped_slice!].
+## Only the parts that are read are loaded from disk, so this works for files much larger than the available memory.
+##
+## ```
+## index = File.map_readonly!("search.idx")?
+## header = File.mapped_slice!(index, 0, 64)
+## ```
+##
+## > If another program shrinks the file while it is mapped, reading the removed part crashes the program.
+##
+## This uses the [memmap2 crate](https://docs.rs/memmap2/latest/memmap2/struct.Mmap.html).
+map_readonly! : Str => Result MappedFile [FileReadErr Path IOErr]
+map_readonly! = |path_str|
+    path = Path.from_str(path_str)
+
+    mapped =
+        Host.file_map_readonly!(InternalPath.to_bytes(path))
+        |> Result.map_err(|err| FileReadErr(path, InternalIOErr.handle_err(err)))?
+
+    Ok(@MappedFile({ mapped, len: Host.file_mapped_len!(mapped) }))
+
+## The size of a [MappedFile] in bytes, at the time it was mapped.
+mapped_len : MappedFile -> U64
+mapped_len = |@MappedFile(inner)|
+    inner.len
+
+## Copies `len` bytes starting at `offset` out of a [MappedFile].
+## Like [List.sublist], this returns fewer bytes when the range goes past the end of the file.
+mapped_slice! : MappedFile, U64, U64 => List U8
+mapped_slice! = |@MappedFile(inner), offset, len|
+    Host.file_mapped_slice!(inner.mapped, offset, len)
+
+## An advisory lock on a file, created with [lock!], [try_lock!] or [lock_shared!].
+##
+## The lock is released with [unlock!], or when the `Lock` is no longer used.
+Lock := { lock : Host.FileLock, path : Path }
+
+## Waits until no other process holds a lock on the file and then takes an exclusive lock.
+## The file is created if it does not exist yet.
+##
+## ```
+## cache_lock = File.lock!("${cache_dir}/.lock")?
+## # Safe to update the cache here
+## File.unlock!(cache_lock)?
+## ```
+##
+## The lock is advisory: it only keeps out processes that also lock the file before using it.
+## Locks are held by the `Lock` value, so locking the same file twice in one program waits too.
+##
+## This uses [flock](https://man7.org/linux/man-pages/man2/flock.2.html).
+lock! : Str => Result Lock [LockErr Path IOErr]
+lock! = |path_str|
+    lock_with!(path_str, Bool.true, Bool.true)
+
+## Takes an exclusive lock on the file like [lock!], but does not wait when it is already locked.
+##
+## ```
+## when File.try_lock!("${cache_dir}/.lock") is
+##     Ok(cache_lock) -> update_cache!(cache_lock)
+##     Err(AlreadyLocked(_)) -> Stdout.line!("Another process is updating the cache.")
+##     Err(LockErr(_, err)) -> Err(err)
+## ```
+try_lock! : Str => Result Lock [AlreadyLocked Path, LockErr Path IOErr]
+try_lock! = |path_str|
+    when lock_with!(path_str, Bool.true, Bool.false) is
+        Err(LockErr(path, WouldBlock)) -> Err(AlreadyLocked(path))
+        Err(LockErr(path, err)) -> Err(LockErr(path, err))
+        Ok(lock) -> Ok(lock)
+
+## Waits until no other process holds an exclusive lock on the file and then takes a shared lock.
+## Any number of processes can hold a shared lock at the same time, useful for reading.
+## The file is created if it does not exist yet.
+lock_shared! : Str => Result Lock [LockErr Path IOErr]
+lock_shared! = |path_str|
+    lock_with!(path_str, Bool.false, Bool.true)
+
+lock_with! : Str, Bool, Bool => Result Lock [LockErr Path IOErr]
+lock_with! = |path_str, exclusive, blocking|
+    path = Path.from_str(path_str)
+
+    Host.file_lock!(InternalPath.to_bytes(path), exclusive, blocking)
+    |> Result.map_ok(|lock| @Lock({ lock, path }))
+    |> Result.map_err(|err| LockErr(path, InternalIOErr.handle_err(err)))
+
+## Releases a [Lock] right away, instead of when it is no longer used.
+unlock! : Lock => Result {} [LockErr Path IOErr]
+unlock! = |@Lock({ lock, path })|
+    Host.file_unlock!(lock)
+    |> Result.map_err(|err| LockErr(path, InternalIOErr.handle_err(err)))
+
+## Reads an extended attribute of a file, like `getfattr`. Symbolic links are followed.
+##
+## ```
+## when File.get_xattr!("artifact.tar", "user.sha256") is
+##     Ok(checksum) -> verify!(checksum)
+##     Err(XattrNotFound(_)) -> compute_checksum!("artifact.tar")
+##     Err(FileReadErr(_, err)) -> Err(err)
+## ```
+##
+## Filesystems without extended attributes give a `FileReadErr` with `Unsupported`.
+## Extended attributes are supported on Linux and macOS.
+get_xattr! : Str, Str => Result (List U8) [XattrNotFound Str, FileReadErr Path IOErr]
+get_xattr! = |path_str, name|
+    path = Path.from_str(path_str)
+
+    when Host.file_get_xattr!(InternalPath.to_bytes(path), name) is
+        Ok(value) -> Ok(value)
+        Err(Ok({})) -> Err(XattrNotFound(name))
+        Err(Err(err)) -> Err(FileReadErr(path, InternalIOErr.handle_err(err)))
+
+## Sets an extended attribute of a file, like `setfattr`. An existing value is replaced.
+##
+## ```
+## File.set_xattr!("artifact.tar", "user.sha256", checksum)?
+## ```
+##
+## On Linux, unprivileged programs can only use names that start with `user.`.
+set_xattr! : Str, Str, List U8 => Result {} [FileWriteErr Path IOErr]
+set_xattr! = |path_str, name, value|
+    path = Path.from_str(path_str)
+
+    Host.file_set_xattr!(InternalPath.to_bytes(path), name, value)
+    |> Result.map_err(|err| FileWriteErr(path, InternalIOErr.handle_err(err)))
+
+## Lists the names of the extended attributes of a file, like `getfattr --dump`.
+list_xattrs! : Str => Result (List Str) [FileReadErr Path IOErr]
+list_xattrs! = |path_str|
+    path = Path.from_str(path_str)
+
+    Host.file_list_xattrs!(InternalPath.to_bytes(path))
+    |> Result.map_err(|err| FileReadErr(path, InternalIOErr.handle_err(err)))
+
+## Removes an extended attribute from a file, like `setfattr --remove`.
+remove_xattr! : Str, Str => Result {} [XattrNotFound Str, FileWriteErr Path IOErr]
+remove_xattr! = |path_str, name|
+    path = Path.from_str(path_str)
+
+    when Host.file_remove_xattr!(InternalPath.to_bytes(path), name) is
+        Ok({}) -> Ok({})
+        Err(Ok({})) -> Err(XattrNotFound(name))
+        Err(Err(err)) -> Err(FileWriteErr(path, InternalIOErr.handle_err(err)))
+
 ## Returns the size of a file in bytes.
 ## 
 ## This uses [rust's std::fs::Metadata::len](https://doc.rust-lang.org/std/fs/struct.Metadata.html#method.len).
@@ -353,3 +641,86 @@ size_in_bytes! : Str => Result U64 [PathErr IOErr]
 size_in_bytes! = |path_str|
     Host.file_size_in_bytes!(InternalPath.to_bytes(Path.from_str(path_str)))
     |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Sets the permission bits of a file, like `chmod`.
+##
+## ```
+## # Make a script executable: rwxr-xr-x
+## File.set_permissions!("install.sh", 0o755)?
+## ```
+##
+## > [Path.set_permissions!] does the same thing, except it takes a [Path] instead of a [Str].
+set_permissions! : Str, U32 => Result {} [PathErr IOErr]
+set_permissions! = |path_str, mode|
+    Path.set_permissions!(Path.from_str(path_str), mode)
+
+## Changes the owner and group of a file, like `chown`.
+##
+## ```
+## File.chown!("config.toml", Uid(0), SameGid)?
+## ```
+##
+## > [Path.chown!] does the same thing, except it takes a [Path] instead of a [Str].
+chown! : Str, [Uid U32, SameUid], [Gid U32, SameGid] => Result {} [PathErr IOErr]
+chown! = |path_str, uid, gid|
+    Path.chown!(Path.from_str(path_str), uid, gid)
+
+## Sets the last access and last modification time of a file, like `touch`.
+##
+## ```
+## now = Utc.now!({})
+## File.set_times!("build.stamp", { accessed: now, modified: now })?
+## ```
+##
+## > [Path.set_times!] does the same thing, except it takes a [Path] instead of a [Str].
+set_times! : Str, { accessed : Utc, modified : Utc } => Result {} [PathErr IOErr]
+set_times! = |path_str, times|
+    Path.set_times!(Path.from_str(path_str), times)
+
+## Changes the size of a file to `len` bytes. Bytes past `len` are removed and a file that grows is filled with zeros.
+##
+## ```
+## # Reserve room for a 1 GiB download
+## File.set_len!("download.part", 1_073_741_824)?
+## ```
+##
+## On most filesystems the added zeros do not take up any disk space until they are written to, the file is sparse.
+##
+## This uses [rust's std::fs::File::set_len](https://doc.rust-lang.org/std/fs/struct.File.html#method.set_len).
+set_len! : Str, U64 => Result {} [FileWriteErr Path IOErr]
+set_len! = |path_str, len|
+    set_len_with!(path_str, len, Bool.false)
+
+## Cuts a file off after `len` bytes, like `truncate --size`. A file that is already shorter is left as it is.
+##
+## ```
+## # Drop a partially written last record
+## File.truncate!("events.log", last_complete_offset)?
+## ```
+truncate! : Str, U64 => Result {} [FileWriteErr Path IOErr]
+truncate! = |path_str, len|
+    set_len_with!(path_str, len, Bool.true)
+
+set_len_with! : Str, U64, Bool => Result {} [FileWriteErr Path IOErr]
+set_len_with! = |path_str, len, only_shrink|
+    path = Path.from_str(path_str)
+
+    Host.file_set_len!(InternalPath.to_bytes(path), len, only_shrink)
+    |> Result.map_err(|err| FileWriteErr(path, InternalIOErr.handle_err(err)))
+
+## Creates a named pipe, like `mkfifo`. Whatever one program writes to it, another program can read from it.
+##
+## ```
+## File.create_fifo!("/tmp/progress", 0o600)?
+## ```
+##
+## The `mode` is combined with the umask, like it is for new files. Opening a named pipe waits until the other side is opened too.
+## Named pipes are not supported on Windows.
+##
+## This uses [mkfifo](https://man7.org/linux/man-pages/man3/mkfifo.3.html).
+create_fifo! : Str, U32 => Result {} [FileWriteErr Path IOErr]
+create_fifo! = |path_str, mode|
+    path = Path.from_str(path_str)
+
+    Host.file_create_fifo!(InternalPath.to_bytes(path), mode)
+    |> Result.map_err(|err| FileWriteErr(path, InternalIOErr.handle_err(err)))
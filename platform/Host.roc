@@ -1,6 +1,11 @@
 hosted [
     FileReader,
+    FileLock,
+    MappedFile,
+    DirWalker,
+    Watcher,
     TcpStream,
+    HttpResponseStream,
     command_exec_output!,
     command_exec_exit_code!,
     current_arch_os!,
@@ -9,33 +14,71 @@ hosted [
     dir_create_all!,
     dir_delete_all!,
     dir_delete_empty!,
+    dir_copy_all!,
     dir_list!,
+    dir_walk!,
+    dir_walk_next!,
+    dir_disk_usage!,
+    dir_size_recursive!,
     env_dict!,
     env_var!,
     exe_path!,
     file_delete!,
     file_exists!,
     file_read_bytes!,
+    file_read_into!,
     file_reader!,
     file_read_line!,
+    file_map_readonly!,
+    file_mapped_len!,
+    file_mapped_slice!,
+    file_lock!,
+    file_unlock!,
+    file_get_xattr!,
+    file_set_xattr!,
+    file_list_xattrs!,
+    file_remove_xattr!,
     file_size_in_bytes!,
     file_write_bytes!,
     file_write_utf8!,
+    file_write_atomic!,
     file_is_executable!,
     file_is_readable!,
     file_is_writable!,
     file_time_accessed!,
     file_time_modified!,
     file_time_created!,
+    file_set_permissions!,
+    file_set_len!,
+    file_create_fifo!,
+    file_chown!,
+    file_set_times!,
     file_rename!,
+    file_copy!,
     get_locale!,
     get_locales!,
     hard_link!,
+    sym_link!,
+    read_link!,
+    canonicalize!,
+    path_glob!,
     path_type!,
+    path_metadata!,
     posix_time!,
     random_u64!,
     random_u32!,
+    watcher_new!,
+    watcher_watch!,
+    watcher_unwatch!,
+    watcher_next!,
     send_request!,
+    http_send_all!,
+    http_configure_pool!,
+    http_configure_tls!,
+    http_send_streaming!,
+    http_send_file!,
+    http_stream_next_chunk!,
+    http_stream_to_file!,
     set_cwd!,
     sleep_millis!,
     sqlite_bind!,
@@ -59,6 +102,9 @@ hosted [
     tcp_read_up_to!,
     tcp_write!,
     temp_dir!,
+    temp_file_create!,
+    temp_dir_create!,
+    temp_keep!,
     tty_mode_canonical!,
     tty_mode_raw!,
 ]
@@ -75,8 +121,10 @@ command_exec_output! : InternalCmd.Command => Result InternalCmd.OutputFromHostS
 # FILE
 file_write_bytes! : List U8, List U8 => Result {} InternalIOErr.IOErrFromHost
 file_write_utf8! : List U8, Str => Result {} InternalIOErr.IOErrFromHost
+file_write_atomic! : List U8, List U8 => Result {} InternalIOErr.IOErrFromHost
 file_delete! : List U8 => Result {} InternalIOErr.IOErrFromHost
 file_read_bytes! : List U8 => Result (List U8) InternalIOErr.IOErrFromHost
+file_read_into! : List U8, List U8 => Result (List U8) InternalIOErr.IOErrFromHost
 file_size_in_bytes! : List U8 => Result U64 InternalIOErr.IOErrFromHost
 file_exists! : List U8 => Result Bool InternalIOErr.IOErrFromHost
 file_is_executable! : List U8 => Result Bool InternalIOErr.IOErrFromHost
@@ -85,22 +133,59 @@ file_is_writable! : List U8 => Result Bool InternalIOErr.IOErrFromHost
 file_time_accessed! : List U8 => Result U128 InternalIOErr.IOErrFromHost
 file_time_modified! : List U8 => Result U128 InternalIOErr.IOErrFromHost
 file_time_created! : List U8 => Result U128 InternalIOErr.IOErrFromHost
+file_set_permissions! : List U8, U32 => Result {} InternalIOErr.IOErrFromHost
+file_set_len! : List U8, U64, Bool => Result {} InternalIOErr.IOErrFromHost
+file_create_fifo! : List U8, U32 => Result {} InternalIOErr.IOErrFromHost
+file_chown! : List U8, U32, U32 => Result {} InternalIOErr.IOErrFromHost
+file_set_times! : List U8, InternalPath.InternalFileTimes => Result {} InternalIOErr.IOErrFromHost
 file_rename! : List U8, List U8 => Result {} InternalIOErr.IOErrFromHost
+file_copy! : List U8, List U8, U8, Bool, Bool => Result U64 InternalIOErr.IOErrFromHost
 
 FileReader := Box {}
 file_reader! : List U8, U64 => Result FileReader InternalIOErr.IOErrFromHost
 file_read_line! : FileReader => Result (List U8) InternalIOErr.IOErrFromHost
 
+MappedFile := Box {}
+file_map_readonly! : List U8 => Result MappedFile InternalIOErr.IOErrFromHost
+file_mapped_len! : MappedFile => U64
+file_mapped_slice! : MappedFile, U64, U64 => List U8
+
+FileLock := Box {}
+file_lock! : List U8, Bool, Bool => Result FileLock InternalIOErr.IOErrFromHost
+file_unlock! : FileLock => Result {} InternalIOErr.IOErrFromHost
+
+# The inner `Ok` means the file has no attribute with that name
+file_get_xattr! : List U8, Str => Result (List U8) (Result {} InternalIOErr.IOErrFromHost)
+file_set_xattr! : List U8, Str, List U8 => Result {} InternalIOErr.IOErrFromHost
+file_list_xattrs! : List U8 => Result (List Str) InternalIOErr.IOErrFromHost
+file_remove_xattr! : List U8, Str => Result {} (Result {} InternalIOErr.IOErrFromHost)
+
 dir_list! : List U8 => Result (List (List U8)) InternalIOErr.IOErrFromHost
 dir_create! : List U8 => Result {} InternalIOErr.IOErrFromHost
 dir_create_all! : List U8 => Result {} InternalIOErr.IOErrFromHost
 dir_delete_empty! : List U8 => Result {} InternalIOErr.IOErrFromHost
 dir_delete_all! : List U8 => Result {} InternalIOErr.IOErrFromHost
+dir_copy_all! : List U8, List U8, U8, U8, Bool, Bool => Result {} InternalIOErr.IOErrFromHost
+
+DirWalker := Box {}
+dir_walk! : List U8, U64, Bool, Bool, Bool => Result DirWalker InternalIOErr.IOErrFromHost
+dir_walk_next! : DirWalker => Result (List InternalPath.InternalWalkEntry) InternalIOErr.IOErrFromHost
+
+dir_disk_usage! : List U8 => Result InternalPath.InternalDiskUsage InternalIOErr.IOErrFromHost
+dir_size_recursive! : List U8 => Result InternalPath.InternalDirSize InternalIOErr.IOErrFromHost
 
 hard_link! : List U8, List U8 => Result {} InternalIOErr.IOErrFromHost
+sym_link! : List U8, List U8 => Result {} InternalIOErr.IOErrFromHost
+read_link! : List U8 => Result (List U8) InternalIOErr.IOErrFromHost
+canonicalize! : List U8 => Result (List U8) InternalIOErr.IOErrFromHost
+path_glob! : Str => Result InternalPath.InternalGlobResult InternalIOErr.IOErrFromHost
 path_type! : List U8 => Result InternalPath.InternalPathType InternalIOErr.IOErrFromHost
+path_metadata! : List U8, Bool => Result InternalPath.InternalMetadata InternalIOErr.IOErrFromHost
 cwd! : {} => Result (List U8) {}
 temp_dir! : {} => List U8
+temp_file_create! : Str, Str => Result (List U8) InternalIOErr.IOErrFromHost
+temp_dir_create! : Str, Str => Result (List U8) InternalIOErr.IOErrFromHost
+temp_keep! : List U8 => {}
 
 # STDIO
 stdout_line! : Str => Result {} InternalIOErr.IOErrFromHost
@@ -114,7 +199,16 @@ stdin_bytes! : {} => Result (List U8) InternalIOErr.IOErrFromHost
 stdin_read_to_end! : {} => Result (List U8) InternalIOErr.IOErrFromHost
 
 # TCP
-send_request! : InternalHttp.RequestToAndFromHost => InternalHttp.ResponseToAndFromHost
+send_request! : InternalHttp.RequestToAndFromHost => Result InternalHttp.ResponseToAndFromHost InternalHttp.HttpErrFromHost
+http_send_all! : List InternalHttp.RequestToAndFromHost, U64, U64 => List (Result InternalHttp.ResponseToAndFromHost InternalHttp.HttpErrFromHost)
+http_configure_pool! : U64, U64 => {}
+http_configure_tls! : { root_certs : List Str, client_cert_chain : Str, client_private_key : Str, insecure_skip_verify : Bool } => Result {} InternalHttp.HttpErrFromHost
+
+HttpResponseStream := Box {}
+http_send_streaming! : InternalHttp.RequestToAndFromHost => Result { body : HttpResponseStream, final_uri : Str, headers : List InternalHttp.Header, redirect_chain : List Str, status : U16, version : InternalHttp.HttpVersion } InternalHttp.HttpErrFromHost
+http_stream_next_chunk! : HttpResponseStream => Result (List U8) InternalHttp.HttpErrFromHost
+http_stream_to_file! : HttpResponseStream, List U8 => Result U64 [Http InternalHttp.HttpErrFromHost, Io InternalIOErr.IOErrFromHost]
+http_send_file! : InternalHttp.RequestToAndFromHost, FileReader => Result InternalHttp.ResponseToAndFromHost InternalHttp.HttpErrFromHost
 
 TcpStream := Box {}
 tcp_connect! : Str, U16 => Result TcpStream Str
@@ -151,3 +245,9 @@ set_cwd! : List U8 => Result {} {}
 
 random_u64! : {} => Result U64 InternalIOErr.IOErrFromHost
 random_u32! : {} => Result U32 InternalIOErr.IOErrFromHost
+
+Watcher := Box {}
+watcher_new! : {} => Result Watcher InternalIOErr.IOErrFromHost
+watcher_watch! : Watcher, List U8, Bool => Result {} InternalIOErr.IOErrFromHost
+watcher_unwatch! : Watcher, List U8 => Result {} InternalIOErr.IOErrFromHost
+watcher_next! : Watcher, U64, U64 => Result (List InternalPath.InternalWatchEvent) InternalIOErr.IOErrFromHost
@@ -10,10 +10,18 @@ module [
     ResponseToAndFromHost,
     Method,
     Header,
+    HttpVersion,
+    ContentEncoding,
+    RedirectPolicy,
+    Proxy,
+    HttpErr,
+    HttpErrFromHost,
     to_host_request,
     to_host_response,
     from_host_request,
     from_host_response,
+    from_host_err,
+    to_host_timeout,
 ]
 
 # FOR ROC
@@ -30,14 +38,38 @@ Request : {
     uri : Str,
     body : List U8,
     timeout_ms : [TimeoutMilliseconds U64, NoTimeout],
+    redirect_policy : RedirectPolicy,
+    proxy : Proxy,
+    accept_encoding : List ContentEncoding,
 }
 
+RedirectPolicy : [NoFollow, FollowUpTo U64, FollowSameOriginUpTo U64]
+
+Proxy : [FromEnv, Direct, Via Str]
+
+ContentEncoding : [Gzip, Deflate, Br, Zstd]
+
 Response : {
     status : U16,
     headers : List Header,
     body : List U8,
+    final_uri : Str,
+    redirect_chain : List Str,
+    version : HttpVersion,
 }
 
+HttpVersion : [Http09, Http10, Http11, Http2, Http3]
+
+HttpErr : [
+    Timeout,
+    ConnectionRefused,
+    DnsFailure,
+    TlsError Str,
+    BadBody,
+    InvalidRequest Str,
+    Other Str,
+]
+
 # FOR HOST
 
 RequestToAndFromHost : {
@@ -47,29 +79,58 @@ RequestToAndFromHost : {
     uri : Str,
     body : List U8,
     timeout_ms : U64,
+    redirect_limit : U64,
+    redirect_same_origin : Bool,
+    proxy_from_env : Bool,
+    proxy_uri : Str,
+    accept_encoding : List ContentEncoding,
 }
 
 ResponseToAndFromHost : {
     status : U16,
     headers : List Header,
     body : List U8,
+    final_uri : Str,
+    redirect_chain : List Str,
+    version : HttpVersion,
+}
+
+HttpErrFromHost : {
+    tag : [
+        BadBody,
+        ConnectionRefused,
+        DnsFailure,
+        InvalidRequest,
+        Other,
+        Timeout,
+        TlsError,
+    ],
+    msg : Str,
 }
 
 to_host_response : Response -> ResponseToAndFromHost
-to_host_response = |{ status, headers, body }| {
+to_host_response = |{ status, headers, body, final_uri, redirect_chain, version }| {
     status,
     headers,
     body,
+    final_uri,
+    redirect_chain,
+    version,
 }
 
 to_host_request : Request -> RequestToAndFromHost
-to_host_request = |{ method, headers, uri, body, timeout_ms }| {
+to_host_request = |{ method, headers, uri, body, timeout_ms, redirect_policy, proxy, accept_encoding }| {
     method: to_host_method(method),
     method_ext: to_host_method_ext(method),
     headers,
     uri,
     body,
     timeout_ms: to_host_timeout(timeout_ms),
+    redirect_limit: to_host_redirect_limit(redirect_policy),
+    redirect_same_origin: redirect_policy_is_same_origin(redirect_policy),
+    proxy_from_env: proxy == FromEnv,
+    proxy_uri: to_host_proxy_uri(proxy),
+    accept_encoding,
 }
 
 to_host_method : Method -> _
@@ -92,6 +153,27 @@ to_host_method_ext = |method|
         EXTENSION(ext) -> ext
         _ -> ""
 
+to_host_redirect_limit : RedirectPolicy -> U64
+to_host_redirect_limit = |policy|
+    when policy is
+        NoFollow -> 0
+        FollowUpTo(max) | FollowSameOriginUpTo(max) -> max
+
+redirect_policy_is_same_origin : RedirectPolicy -> Bool
+redirect_policy_is_same_origin = |policy|
+    when policy is
+        FollowSameOriginUpTo(_) -> Bool.true
+        NoFollow | FollowUpTo(_) -> Bool.false
+
+expect to_host_redirect_limit(NoFollow) == 0
+expect to_host_redirect_limit(FollowSameOriginUpTo(5)) == 5
+
+to_host_proxy_uri : Proxy -> Str
+to_host_proxy_uri = |proxy|
+    when proxy is
+        Via(proxy_uri) -> proxy_uri
+        FromEnv | Direct -> ""
+
 to_host_timeout : _ -> U64
 to_host_timeout = |timeout|
     when timeout is
@@ -99,14 +181,38 @@ to_host_timeout = |timeout|
         NoTimeout -> 0
 
 from_host_request : RequestToAndFromHost -> Request
-from_host_request = |{ method, method_ext, headers, uri, body, timeout_ms }| {
+from_host_request = |{ method, method_ext, headers, uri, body, timeout_ms, redirect_limit, redirect_same_origin, proxy_from_env, proxy_uri, accept_encoding }| {
     method: from_host_method(method, method_ext),
     headers,
     uri,
     body,
     timeout_ms: from_host_timeout(timeout_ms),
+    redirect_policy: from_host_redirect_policy(redirect_limit, redirect_same_origin),
+    proxy: from_host_proxy(proxy_from_env, proxy_uri),
+    accept_encoding,
 }
 
+from_host_redirect_policy : U64, Bool -> RedirectPolicy
+from_host_redirect_policy = |limit, same_origin|
+    if limit == 0 then
+        NoFollow
+    else if same_origin then
+        FollowSameOriginUpTo(limit)
+    else
+        FollowUpTo(limit)
+
+from_host_proxy : Bool, Str -> Proxy
+from_host_proxy = |from_env, proxy_uri|
+    if from_env then
+        FromEnv
+    else if Str.is_empty(proxy_uri) then
+        Direct
+    else
+        Via(proxy_uri)
+
+expect from_host_proxy(Bool.false, "") == Direct
+expect from_host_proxy(Bool.false, "http://proxy:3128") == Via("http://proxy:3128")
+
 from_host_method : U64, Str -> Method
 from_host_method = |tag, ext|
     when tag is
@@ -132,8 +238,25 @@ expect from_host_timeout(0) == NoTimeout
 expect from_host_timeout(1) == TimeoutMilliseconds(1)
 
 from_host_response : ResponseToAndFromHost -> Response
-from_host_response = |{ status, headers, body }| {
+from_host_response = |{ status, headers, body, final_uri, redirect_chain, version }| {
     status,
     headers,
     body,
+    final_uri,
+    redirect_chain,
+    version,
 }
+
+from_host_err : HttpErrFromHost -> HttpErr
+from_host_err = |{ tag, msg }|
+    when tag is
+        BadBody -> BadBody
+        ConnectionRefused -> ConnectionRefused
+        DnsFailure -> DnsFailure
+        InvalidRequest -> InvalidRequest(msg)
+        Other -> Other(msg)
+        Timeout -> Timeout
+        TlsError -> TlsError(msg)
+
+expect from_host_err({ tag: Timeout, msg: "" }) == Timeout
+expect from_host_err({ tag: TlsError, msg: "invalid peer certificate: UnknownIssuer" }) == TlsError("invalid peer certificate: UnknownIssuer")
@@ -6,6 +6,8 @@ module [
 
 ## **NotFound** - An entity was not found, often a file.
 ##
+## **NotADirectory** - A directory was expected, but the path is a file or something else.
+##
 ## **PermissionDenied** - The operation lacked the necessary privileges to complete.
 ##
 ## **BrokenPipe** - The operation failed because a pipe was closed.
@@ -18,15 +20,19 @@ module [
 ##
 ## **OutOfMemory** - An operation could not be completed, because it failed to allocate enough memory.
 ##
+## **WouldBlock** - The operation would have to wait, for example for a lock that is held by someone else.
+##
 ## **Other** - A custom error that does not fall under any other I/O error kind.
 IOErr : [
     NotFound,
+    NotADirectory,
     PermissionDenied,
     BrokenPipe,
     AlreadyExists,
     Interrupted,
     Unsupported,
     OutOfMemory,
+    WouldBlock,
     Other Str,
 ]
 
@@ -34,12 +40,14 @@ IOErrFromHost : {
     tag : [
         EndOfFile,
         NotFound,
+        NotADirectory,
         PermissionDenied,
         BrokenPipe,
         AlreadyExists,
         Interrupted,
         Unsupported,
         OutOfMemory,
+        WouldBlock,
         Other,
     ],
     msg : Str,
@@ -49,10 +57,12 @@ handle_err : IOErrFromHost -> IOErr
 handle_err = |{ tag, msg }|
     when tag is
         NotFound -> NotFound
+        NotADirectory -> NotADirectory
         PermissionDenied -> PermissionDenied
         BrokenPipe -> BrokenPipe
         AlreadyExists -> AlreadyExists
         Interrupted -> Interrupted
         Unsupported -> Unsupported
         OutOfMemory -> OutOfMemory
+        WouldBlock -> WouldBlock
         Other | EndOfFile -> Other(msg)
@@ -2,6 +2,13 @@ module [
     UnwrappedPath,
     InternalPath,
     InternalPathType,
+    InternalMetadata,
+    InternalFileTimes,
+    InternalWalkEntry,
+    InternalGlobResult,
+    InternalWatchEvent,
+    InternalDiskUsage,
+    InternalDirSize,
     wrap,
     unwrap,
     to_bytes,
@@ -9,6 +16,8 @@ module [
     from_os_bytes,
 ]
 
+import InternalIOErr
+
 InternalPath := UnwrappedPath implements [Inspect]
 
 UnwrappedPath : [
@@ -53,6 +62,77 @@ InternalPathType : {
     is_dir : Bool,
 }
 
+# Do not change the order of the tags! It needs to match InternalFileType in the host.
+InternalMetadata : {
+    accessed : U128,
+    changed : U128,
+    created : U128,
+    modified : U128,
+    device : U64,
+    inode : U64,
+    nlink : U64,
+    size : U64,
+    gid : U32,
+    mode : U32,
+    uid : U32,
+    file_type : [
+        IsBlockDevice,
+        IsCharDevice,
+        IsDir,
+        IsFifo,
+        IsFile,
+        IsOther,
+        IsSocket,
+        IsSymLink,
+    ],
+}
+
+# Nanoseconds since the UNIX epoch
+InternalFileTimes : {
+    accessed : I128,
+    modified : I128,
+}
+
+# Do not change the order of the tags! It needs to match InternalFileType in the host.
+InternalWalkEntry : {
+    depth : U64,
+    path : List U8,
+    file_type : [
+        IsBlockDevice,
+        IsCharDevice,
+        IsDir,
+        IsFifo,
+        IsFile,
+        IsOther,
+        IsSocket,
+        IsSymLink,
+    ],
+}
+
+InternalGlobResult : {
+    errors : List { err : InternalIOErr.IOErrFromHost, path : List U8 },
+    matches : List (List U8),
+}
+
+# `from` is only set for Renamed. Do not change the order of the tags!
+InternalWatchEvent : {
+    from : List U8,
+    path : List U8,
+    kind : [Created, Modified, Removed, Renamed],
+}
+
+InternalDiskUsage : {
+    available : U64,
+    free : U64,
+    fs_type : Str,
+    total : U64,
+}
+
+InternalDirSize : {
+    allocated : U64,
+    apparent : U64,
+}
+
 wrap : UnwrappedPath -> InternalPath
 wrap = @InternalPath
 
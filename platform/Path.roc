@@ -1,20 +1,28 @@
 module [
     Path,
     IOErr,
+    Metadata,
+    CopyOptions,
+    DirCopyOptions,
+    GlobResult,
     display,
     from_str,
     from_bytes,
     with_extension,
+    matches_glob,
     is_dir!,
     is_file!,
     is_sym_link!,
     exists!,
     type!,
+    metadata!,
     write_utf8!,
     write_bytes!,
+    write_atomic!,
     write!,
     read_utf8!,
     read_bytes!,
+    read_into!,
     delete!,
     list_dir!,
     create_dir!,
@@ -22,12 +30,22 @@ module [
     delete_empty!,
     delete_all!,
     hard_link!,
+    sym_link!,
+    read_link!,
+    canonicalize!,
+    glob!,
     rename!,
+    copy!,
+    copy_all!,
+    set_permissions!,
+    chown!,
+    set_times!,
 ]
 
 import InternalPath
 import InternalIOErr
 import Host
+import Utc exposing [Utc]
 
 ## Represents a path to a file or directory on the filesystem.
 Path : InternalPath.InternalPath
@@ -37,6 +55,77 @@ Path : InternalPath.InternalPath
 ## > This is the same as [`File.Err`](File#Err).
 IOErr : InternalIOErr.IOErr
 
+## Everything the filesystem knows about a path, as returned by [metadata!].
+##
+## ```
+## {
+##     type : [IsFile, IsDir, IsSymLink, IsFifo, IsSocket, IsBlockDevice, IsCharDevice, IsOther],
+##     size : U64,
+##     accessed : Utc,
+##     modified : Utc,
+##     changed : Utc, # when the metadata (e.g. permissions) last changed
+##     created : Result Utc [Unsupported],
+##     mode : U32, # permission and file type bits, as in `stat`
+##     uid : U32,
+##     gid : U32,
+##     inode : U64,
+##     device : U64,
+##     nlink : U64, # number of hard links
+## }
+## ```
+##
+## Windows does not have all of these, the missing fields are filled with placeholders:
+## - `mode` is `0o444` for read-only files and `0o666` for all others, without file type bits.
+## - `nlink` is always `1`, even for files with several hard links.
+## - `changed` is the same as `modified`.
+## - `uid`, `gid`, `inode` and `device` are `0`.
+Metadata : {
+    type : [IsFile, IsDir, IsSymLink, IsFifo, IsSocket, IsBlockDevice, IsCharDevice, IsOther],
+    size : U64,
+    accessed : Utc,
+    modified : Utc,
+    changed : Utc,
+    created : Result Utc [Unsupported],
+    mode : U32,
+    uid : U32,
+    gid : U32,
+    inode : U64,
+    device : U64,
+    nlink : U64,
+}
+
+## The result of [glob!].
+##
+## `unreadable` contains the directories that could not be read while looking for matches,
+## for example because of missing permissions. Matches inside them are not in `matches`.
+GlobResult : {
+    matches : List Path,
+    unreadable : List { path : Path, err : IOErr },
+}
+
+## Options for [copy!].
+##
+## - `overwrite` decides what happens when the destination already exists.
+## - `preserve_mode` copies the permission bits of the source.
+## - `preserve_times` copies the last access and modification time of the source.
+CopyOptions : {
+    overwrite : [FailIfExists, Overwrite, SkipExisting],
+    preserve_mode : Bool,
+    preserve_times : Bool,
+}
+
+## Options for [copy_all!], these are the [CopyOptions] plus what to do with symbolic links:
+##
+## - `CopyLinks` creates a new link with the same target.
+## - `FollowLinks` copies the file or directory the link points to.
+## - `SkipLinks` leaves them out.
+DirCopyOptions : {
+    overwrite : [FailIfExists, Overwrite, SkipExisting],
+    preserve_mode : Bool,
+    preserve_times : Bool,
+    sym_links : [CopyLinks, FollowLinks, SkipLinks],
+}
+
 ## Write data to a file.
 ##
 ## First encode a `val` using a given `fmt` which implements the ability [Encode.EncoderFormatting](https://www.roc-lang.org/builtins/Encode#EncoderFormatting).
@@ -83,6 +172,26 @@ write_bytes! = |bytes, path|
     Host.file_write_bytes!(path_bytes, bytes)
     |> Result.map_err(|err| FileWriteErr(path, InternalIOErr.handle_err(err)))
 
+## Writes bytes to a file without ever leaving it half-written.
+##
+## ```
+## # Replaces the contents of `config.json`, even if the program crashes midway.
+## Path.write_atomic!(Str.to_utf8("{}"), Path.from_str("config.json"))?
+## ```
+##
+## The bytes are first written to a temporary file in the same directory. That file is
+## flushed to disk and then renamed over the target, so readers see either the old
+## or the new contents. The permissions of an existing file are kept. When the path is a
+## symbolic link, the file it points to is replaced and the link stays in place.
+##
+## > [`File.write_atomic!`](File#write_atomic!) does the same thing, except it takes a [Str] instead of a [Path].
+write_atomic! : List U8, Path => Result {} [FileWriteErr Path IOErr]
+write_atomic! = |bytes, path|
+    path_bytes = InternalPath.to_bytes(path)
+
+    Host.file_write_atomic!(path_bytes, bytes)
+    |> Result.map_err(|err| FileWriteErr(path, InternalIOErr.handle_err(err)))
+
 ## Writes a [Str] to a file, encoded as [UTF-8](https://en.wikipedia.org/wiki/UTF-8).
 ##
 ## ```
@@ -228,6 +337,46 @@ type! = |path|
                 IsFile,
     )
 
+## Returns the [Metadata] of a path, using a single `stat` call.
+##
+## ```
+## info = Path.metadata!(Path.from_str("myfile.txt"), FollowSymLinks)?
+##
+## Stdout.line!("${Num.to_str(info.size)} bytes")?
+## ```
+##
+## With `FollowSymLinks` the metadata of the file a symbolic link points to is returned.
+## With `NoFollowSymLinks` you get the metadata of the link itself.
+##
+## This uses [rust's std::fs::metadata](https://doc.rust-lang.org/std/fs/fn.metadata.html)
+## or [std::fs::symlink_metadata](https://doc.rust-lang.org/std/fs/fn.symlink_metadata.html).
+##
+## > [`File.metadata!`](File#metadata!) does the same thing, except it takes a [Str] instead of a [Path].
+metadata! : Path, [FollowSymLinks, NoFollowSymLinks] => Result Metadata [PathErr IOErr]
+metadata! = |path, follow|
+    Host.path_metadata!(InternalPath.to_bytes(path), follow == FollowSymLinks)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+    |> Result.map_ok(from_host_metadata)
+
+from_host_metadata : InternalPath.InternalMetadata -> Metadata
+from_host_metadata = |host|
+    to_utc = |nanos| Num.to_i128(nanos) |> Utc.from_nanos_since_epoch
+
+    {
+        type: host.file_type,
+        size: host.size,
+        accessed: to_utc(host.accessed),
+        modified: to_utc(host.modified),
+        changed: to_utc(host.changed),
+        created: if host.created == 0 then Err(Unsupported) else Ok(to_utc(host.created)),
+        mode: host.mode,
+        uid: host.uid,
+        gid: host.gid,
+        inode: host.inode,
+        device: host.device,
+        nlink: host.nlink,
+    }
+
 ## If the last component of this path has no `.`, appends `.` followed by the given string.
 ## Otherwise, replaces everything after the last `.` with the given string.
 ##
@@ -266,6 +415,136 @@ with_extension = |path, extension|
             |> FromStr
             |> InternalPath.wrap
 
+## Returns true if the path matches the glob pattern, this does not touch the filesystem.
+## The pattern syntax is the same as for [glob!].
+##
+## ```
+## expect Path.matches_glob(Path.from_str("src/parser/Lexer.roc"), "src/**/*.{roc,rs}")
+## expect !Path.matches_glob(Path.from_str("tests/Lexer.roc"), "src/**/*.roc")
+## ```
+##
+## Empty and `.` components are ignored, so `./src//main.roc` matches `src/main.roc`.
+matches_glob : Path, Str -> Bool
+matches_glob = |path, pattern|
+    path_bytes = InternalPath.to_bytes(path)
+    pattern_bytes = Str.to_utf8(pattern)
+    path_segments = glob_segments(path_bytes)
+
+    List.starts_with(path_bytes, ['/']) == List.starts_with(pattern_bytes, ['/'])
+    and List.any(expand_braces(pattern_bytes), |expanded| match_segments(glob_segments(expanded), path_segments))
+
+glob_segments : List U8 -> List (List U8)
+glob_segments = |bytes|
+    List.split_on(bytes, '/')
+    |> List.drop_if(|segment| segment == [] or segment == ['.'])
+
+# Expands the first `{a,b}` group into one pattern per alternative, the results are expanded recursively.
+expand_braces : List U8 -> List (List U8)
+expand_braces = |pattern|
+    when List.find_first_index(pattern, |byte| byte == '{') is
+        Err(NotFound) -> [pattern]
+        Ok(open_index) ->
+            when split_alternatives(List.drop_first(pattern, open_index + 1), 0, [], []) is
+                Ok({ alternatives, rest }) ->
+                    prefix = List.take_first(pattern, open_index)
+                    List.join_map(alternatives, |alternative| expand_braces(prefix |> List.concat(alternative) |> List.concat(rest)))
+
+                # An unclosed `{` is matched literally
+                Err(Unclosed) -> [pattern]
+
+split_alternatives : List U8, U64, List U8, List (List U8) -> Result { alternatives : List (List U8), rest : List U8 } [Unclosed]
+split_alternatives = |bytes, depth, current, alternatives|
+    when bytes is
+        [] -> Err(Unclosed)
+        [byte, .. as rest] if byte == '}' and depth == 0 -> Ok({ alternatives: List.append(alternatives, current), rest })
+        [byte, .. as rest] if byte == ',' and depth == 0 -> split_alternatives(rest, depth, [], List.append(alternatives, current))
+        [byte, .. as rest] ->
+            new_depth =
+                if byte == '{' then
+                    depth + 1
+                else if byte == '}' then
+                    depth - 1
+                else
+                    depth
+
+            split_alternatives(rest, new_depth, List.append(current, byte), alternatives)
+
+match_segments : List (List U8), List (List U8) -> Bool
+match_segments = |patterns, segments|
+    when (patterns, segments) is
+        ([], []) -> Bool.true
+        ([pattern, .. as pattern_rest], _) if pattern == ['*', '*'] ->
+            # `**` matches zero or more path components
+            when segments is
+                [_, .. as segment_rest] -> match_segments(pattern_rest, segments) or match_segments(patterns, segment_rest)
+                [] -> match_segments(pattern_rest, segments)
+
+        ([pattern, .. as pattern_rest], [segment, .. as segment_rest]) ->
+            match_segment(pattern, segment) and match_segments(pattern_rest, segment_rest)
+
+        _ -> Bool.false
+
+match_segment : List U8, List U8 -> Bool
+match_segment = |pattern, name|
+    when pattern is
+        [] -> List.is_empty(name)
+        [byte, .. as pattern_rest] if byte == '*' ->
+            List.range({ start: At(0), end: At(List.len(name)) })
+            |> List.any(|skipped| match_segment(pattern_rest, List.drop_first(name, skipped)))
+
+        [byte, .. as pattern_rest] if byte == '?' ->
+            when name is
+                [_, .. as name_rest] -> match_segment(pattern_rest, name_rest)
+                [] -> Bool.false
+
+        [byte, .. as after_bracket] if byte == '[' ->
+            when (name, parse_glob_class(after_bracket)) is
+                ([first, .. as name_rest], Ok(class)) ->
+                    in_class = List.any(class.ranges, |(low, high)| first >= low and first <= high)
+                    in_class != class.negated and match_segment(class.rest, name_rest)
+
+                ([], Ok(_)) -> Bool.false
+                # An unclosed `[` is matched literally
+                (_, Err(Unclosed)) -> match_glob_byte(byte, after_bracket, name)
+
+        [byte, escaped, .. as pattern_rest] if byte == '\\' -> match_glob_byte(escaped, pattern_rest, name)
+        [byte, .. as pattern_rest] -> match_glob_byte(byte, pattern_rest, name)
+
+match_glob_byte : U8, List U8, List U8 -> Bool
+match_glob_byte = |byte, pattern_rest, name|
+    when name is
+        [first, .. as name_rest] if first == byte -> match_segment(pattern_rest, name_rest)
+        _ -> Bool.false
+
+# Parses a character class like `[!a-z]`, `bytes` starts right after the `[`.
+parse_glob_class : List U8 -> Result { negated : Bool, ranges : List (U8, U8), rest : List U8 } [Unclosed]
+parse_glob_class = |bytes|
+    (negated, body) =
+        when bytes is
+            [first, .. as after] if first == '!' or first == '^' -> (Bool.true, after)
+            _ -> (Bool.false, bytes)
+
+    parse_glob_class_ranges(body, [], Bool.true)
+    |> Result.map_ok(|{ ranges, rest }| { negated, ranges, rest })
+
+# A `]` right after the `[` is part of the class, like in `[]a]`.
+parse_glob_class_ranges : List U8, List (U8, U8), Bool -> Result { ranges : List (U8, U8), rest : List U8 } [Unclosed]
+parse_glob_class_ranges = |bytes, ranges, at_start|
+    when bytes is
+        [] -> Err(Unclosed)
+        [byte, .. as rest] if byte == ']' and !at_start -> Ok({ ranges, rest })
+        [low, dash, high, .. as rest] if dash == '-' and high != ']' -> parse_glob_class_ranges(rest, List.append(ranges, (low, high)), Bool.false)
+        [byte, .. as rest] -> parse_glob_class_ranges(rest, List.append(ranges, (byte, byte)), Bool.false)
+
+expect matches_glob(from_str("src/parser/Lexer.roc"), "src/**/*.{roc,rs}")
+expect matches_glob(from_str("src/main.rs"), "src/**/*.{roc,rs}")
+expect !matches_glob(from_str("tests/Lexer.roc"), "src/**/*.roc")
+expect !matches_glob(from_str("src/a/b.roc"), "src/*.roc")
+expect matches_glob(from_str("./src//file1.txt"), "src/file[0-9].txt")
+expect !matches_glob(from_str("src/fileA.txt"), "src/file[!A-Z].txt")
+expect matches_glob(from_str("a.b"), "?.?")
+expect !matches_glob(from_str("/etc/hosts"), "etc/*")
+
 ## Deletes a file from the filesystem.
 ##
 ## Performs a [`DeleteFile`](https://docs.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-deletefile)
@@ -330,6 +609,24 @@ read_bytes! = |path|
     Host.file_read_bytes!(InternalPath.to_bytes(path))
     |> Result.map_err(|err| FileReadErr(path, InternalIOErr.handle_err(err)))
 
+## Reads all the bytes in a file into `buffer`, replacing what it held before.
+##
+## ```
+## # Read many files of similar size with a single allocation.
+## buffer = Path.read_into!(Path.from_str("day1.bin"), List.with_capacity(1_000_000))?
+## # ...use buffer...
+## next = Path.read_into!(Path.from_str("day2.bin"), buffer)?
+## ```
+##
+## The capacity of `buffer` is reused when nothing else refers to the list, so pass it on instead of keeping a copy around.
+## The list grows once to fit the whole file if it is too small, instead of step by step while reading.
+##
+## > [`File.read_into!`](File#read_into!) does the same thing, except it takes a [Str] instead of a [Path].
+read_into! : Path, List U8 => Result (List U8) [FileReadErr Path IOErr]
+read_into! = |path, buffer|
+    Host.file_read_into!(InternalPath.to_bytes(path), buffer)
+    |> Result.map_err(|err| FileReadErr(path, InternalIOErr.handle_err(err)))
+
 ## Lists the files and directories inside the directory.
 ##
 ## > [`Dir.list`](Dir#list!) does the same thing, except it takes a [Str] instead of a [Path].
@@ -406,6 +703,138 @@ hard_link! = |path_original, path_link|
     |> Result.map_err(InternalIOErr.handle_err)
     |> Result.map_err(LinkErr)
 
+## Copies the contents of a file to another file, returns the number of bytes copied.
+##
+## ```
+## Path.copy!(
+##     Path.from_str("release.tar.gz"),
+##     Path.from_str("backup/release.tar.gz"),
+##     { overwrite: Overwrite, preserve_mode: Bool.true, preserve_times: Bool.true },
+## )?
+## ```
+##
+## The data is streamed, so the file never has to fit in memory. On Linux the copy
+## happens inside the kernel with `copy_file_range`, or as a reflink on filesystems like btrfs.
+## When an existing destination is skipped because of `SkipExisting`, 0 is returned.
+## Copying a file onto itself, also through a link, fails with an `Other` error.
+##
+## > [`File.copy!`](File#copy!) does the same thing, except it takes a [Str] instead of a [Path].
+copy! : Path, Path, CopyOptions => Result U64 [PathErr IOErr]
+copy! = |from, to, { overwrite, preserve_mode, preserve_times }|
+    Host.file_copy!(InternalPath.to_bytes(from), InternalPath.to_bytes(to), overwrite_to_u8(overwrite), preserve_mode, preserve_times)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Recursively copies a directory and everything inside it.
+##
+## ```
+## Path.copy_all!(
+##     Path.from_str("assets"),
+##     Path.from_str("dist/assets"),
+##     { overwrite: Overwrite, preserve_mode: Bool.true, preserve_times: Bool.false, sym_links: CopyLinks },
+## )?
+## ```
+##
+## The destination directory is created if it does not exist yet. Files are copied like [copy!] does.
+## Named pipes, sockets and devices are left out, as are links to them with `FollowLinks`.
+## A destination inside the source directory fails with an `Other` error before anything is copied.
+##
+## > [`Dir.copy_all!`](Dir#copy_all!) does the same thing, except it takes a [Str] instead of a [Path].
+copy_all! : Path, Path, DirCopyOptions => Result {} [DirErr IOErr]
+copy_all! = |from, to, { overwrite, preserve_mode, preserve_times, sym_links }|
+    sym_links_u8 =
+        when sym_links is
+            CopyLinks -> 0
+            FollowLinks -> 1
+            SkipLinks -> 2
+
+    Host.dir_copy_all!(InternalPath.to_bytes(from), InternalPath.to_bytes(to), overwrite_to_u8(overwrite), sym_links_u8, preserve_mode, preserve_times)
+    |> Result.map_err(|err| DirErr(InternalIOErr.handle_err(err)))
+
+overwrite_to_u8 : [FailIfExists, Overwrite, SkipExisting] -> U8
+overwrite_to_u8 = |overwrite|
+    when overwrite is
+        FailIfExists -> 0
+        Overwrite -> 1
+        SkipExisting -> 2
+
+## Creates a new [symbolic link](https://en.wikipedia.org/wiki/Symbolic_link) on the filesystem.
+##
+## The link path will be a link pointing to the original path. A relative original path
+## is resolved relative to the directory the link is in, not the current working directory.
+##
+## ```
+## # ~/.vimrc -> dotfiles/vimrc
+## Path.sym_link!(Path.from_str("dotfiles/vimrc"), Path.from_str(".vimrc"))?
+## ```
+##
+## This uses [rust's std::os::unix::fs::symlink](https://doc.rust-lang.org/std/os/unix/fs/fn.symlink.html).
+##
+## > [`File.sym_link!`](File#sym_link!) does the same thing, except it takes a [Str] instead of a [Path].
+sym_link! : Path, Path => Result {} [LinkErr IOErr]
+sym_link! = |path_original, path_link|
+    Host.sym_link!(InternalPath.to_bytes(path_original), InternalPath.to_bytes(path_link))
+    |> Result.map_err(InternalIOErr.handle_err)
+    |> Result.map_err(LinkErr)
+
+## Returns the path a symbolic link points to, exactly as it was stored in the link.
+##
+## This uses [rust's std::fs::read_link](https://doc.rust-lang.org/std/fs/fn.read_link.html).
+##
+## > [`File.read_link!`](File#read_link!) does the same thing, except it takes a [Str] instead of a [Path].
+read_link! : Path => Result Path [PathErr IOErr]
+read_link! = |path|
+    Host.read_link!(InternalPath.to_bytes(path))
+    |> Result.map_ok(InternalPath.from_os_bytes)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Returns the absolute form of the path with all `.` and `..` components removed
+## and all symbolic links resolved. The path has to exist.
+##
+## ```
+## # e.g. /home/user/x/y
+## real_path = Path.canonicalize!(Path.from_str("../x/./y"))?
+## ```
+##
+## This uses [rust's std::fs::canonicalize](https://doc.rust-lang.org/std/fs/fn.canonicalize.html).
+##
+## > [`File.canonicalize!`](File#canonicalize!) does the same thing, except it takes a [Str] instead of a [Path].
+canonicalize! : Path => Result Path [PathErr IOErr]
+canonicalize! = |path|
+    Host.canonicalize!(InternalPath.to_bytes(path))
+    |> Result.map_ok(InternalPath.from_os_bytes)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Finds all paths on the filesystem that match a glob pattern, sorted by path.
+##
+## ```
+## { matches } = Path.glob!("src/**/*.roc")?
+## ```
+##
+## The pattern supports:
+## - `*` matches anything inside a single path component, `?` matches a single byte.
+## - `**` matches any number of directories, including none.
+## - `[abc]`, `[a-z]` and `[!abc]` match a single byte in (or not in) the class.
+## - `{roc,rs}` matches either alternative.
+##
+## Unlike in most shells, `*`, `?` and `**` also match names that start with a `.`.
+## Use a class like `[!.]*` to leave hidden files out.
+##
+## A relative pattern is matched against paths relative to the current working directory.
+## Only the directories below the part of the pattern without wildcards are read, `src` in the example above.
+## Symbolic links to directories are followed, link loops end up in `unreadable`.
+##
+## This uses the [globset crate](https://docs.rs/globset/latest/globset/).
+## Use [matches_glob] to check paths you already have.
+glob! : Str => Result GlobResult [PathErr IOErr]
+glob! = |pattern|
+    Host.path_glob!(pattern)
+    |> Result.map_ok(|{ matches, errors }|
+        {
+            matches: List.map(matches, InternalPath.from_os_bytes),
+            unreadable: List.map(errors, |{ path, err }| { path: InternalPath.from_os_bytes(path), err: InternalIOErr.handle_err(err) }),
+        })
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
 ## Renames a file or directory.
 ##
 ## This uses [rust's std::fs::rename](https://doc.rust-lang.org/std/fs/fn.rename.html).
@@ -415,3 +844,64 @@ rename! = |from, to|
     to_path_bytes = InternalPath.to_bytes(to)
     Host.file_rename!(from_path_bytes, to_path_bytes)
     |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Sets the permission bits of a file or directory, like `chmod`.
+##
+## ```
+## # Make a script executable: rwxr-xr-x
+## Path.set_permissions!(Path.from_str("install.sh"), 0o755)?
+## ```
+##
+## On Windows only the write bits are used; clearing all of them makes the file readonly.
+##
+## This uses [rust's std::fs::set_permissions](https://doc.rust-lang.org/std/fs/fn.set_permissions.html).
+##
+## > [`File.set_permissions!`](File#set_permissions!) does the same thing, except it takes a [Str] instead of a [Path].
+set_permissions! : Path, U32 => Result {} [PathErr IOErr]
+set_permissions! = |path, mode|
+    Host.file_set_permissions!(InternalPath.to_bytes(path), mode)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Changes the owner and group of a file or directory, like `chown`.
+##
+## ```
+## # Give the file to root, keep the group as it is.
+## Path.chown!(Path.from_str("config.toml"), Uid(0), SameGid)?
+## ```
+##
+## This is not supported on Windows and will fail with `Unsupported`.
+##
+## This uses [rust's std::os::unix::fs::chown](https://doc.rust-lang.org/std/os/unix/fs/fn.chown.html).
+##
+## > [`File.chown!`](File#chown!) does the same thing, except it takes a [Str] instead of a [Path].
+chown! : Path, [Uid U32, SameUid], [Gid U32, SameGid] => Result {} [PathErr IOErr]
+chown! = |path, uid, gid|
+    # The host uses -1 for "leave unchanged", just like the chown syscall.
+    host_uid =
+        when uid is
+            Uid(id) -> id
+            SameUid -> Num.max_u32
+
+    host_gid =
+        when gid is
+            Gid(id) -> id
+            SameGid -> Num.max_u32
+
+    Host.file_chown!(InternalPath.to_bytes(path), host_uid, host_gid)
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
+
+## Sets the last access and last modification time of a file or directory, like `touch`.
+##
+## ```
+## now = Utc.now!({})
+## Path.set_times!(Path.from_str("build.stamp"), { accessed: now, modified: now })?
+## ```
+##
+## The file is not opened, so this also works for FIFOs and files without read permission.
+## Times that the platform can not represent result in an `Other` error.
+##
+## > [`File.set_times!`](File#set_times!) does the same thing, except it takes a [Str] instead of a [Path].
+set_times! : Path, { accessed : Utc, modified : Utc } => Result {} [PathErr IOErr]
+set_times! = |path, { accessed, modified }|
+    Host.file_set_times!(InternalPath.to_bytes(path), { accessed: Utc.to_nanos_since_epoch(accessed), modified: Utc.to_nanos_since_epoch(modified) })
+    |> Result.map_err(|err| PathErr(InternalIOErr.handle_err(err)))
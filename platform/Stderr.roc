@@ -44,7 +44,7 @@ handle_err = |{ tag, msg }|
         Interrupted -> StderrErr(Interrupted)
         Unsupported -> StderrErr(Unsupported)
         OutOfMemory -> StderrErr(OutOfMemory)
-        Other | EndOfFile -> StderrErr(Other(msg))
+        Other | EndOfFile | NotADirectory | WouldBlock -> StderrErr(Other(msg))
 
 ## Write the given string to [standard error](https://en.wikipedia.org/wiki/Standard_streams#Standard_error_(stderr)),
 ## followed by a newline.
@@ -45,7 +45,7 @@ handle_err = |{ tag, msg }|
         Unsupported -> StdinErr(Unsupported)
         OutOfMemory -> StdinErr(OutOfMemory)
         EndOfFile -> EndOfFile
-        Other -> StdinErr(Other(msg))
+        Other | NotADirectory | WouldBlock -> StdinErr(Other(msg))
 
 ## Read a line from [standard input](https://en.wikipedia.org/wiki/Standard_streams#Standard_input_(stdin)).
 ##
@@ -85,5 +85,5 @@ read_to_end! = |{}|
                 Unsupported -> StdinErr(Unsupported)
                 OutOfMemory -> StdinErr(OutOfMemory)
                 EndOfFile -> crash("unreachable, reading to EOF")
-                Other -> StdinErr(Other(msg)),
+                Other | NotADirectory | WouldBlock -> StdinErr(Other(msg)),
     )
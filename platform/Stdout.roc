@@ -44,7 +44,7 @@ handle_err = |{ tag, msg }|
         Interrupted -> StdoutErr(Interrupted)
         Unsupported -> StdoutErr(Unsupported)
         OutOfMemory -> StdoutErr(OutOfMemory)
-        Other | EndOfFile -> StdoutErr(Other(msg))
+        Other | EndOfFile | NotADirectory | WouldBlock -> StdoutErr(Other(msg))
 
 ## Write the given string to [standard output](https://en.wikipedia.org/wiki/Standard_streams#Standard_output_(stdout)),
 ## followed by a newline.
@@ -0,0 +1,92 @@
+module [
+    Watcher,
+    Event,
+    IOErr,
+    new!,
+    watch!,
+    unwatch!,
+    next!,
+]
+
+import Path exposing [Path]
+import InternalPath
+import InternalIOErr
+import Host
+
+## Watches files and directories for changes, see [examples/watcher.roc](https://github.com/roc-lang/basic-cli/blob/main/examples/watcher.roc) for example usage.
+##
+## The watches are removed when the `Watcher` is no longer used.
+Watcher := Host.Watcher
+
+## A change to a watched file or directory.
+##
+## `Renamed` is only reported when both the old and the new path are watched,
+## moving a file into or out of a watched directory is reported as `Created` or `Removed`.
+Event : [
+    Created Path,
+    Modified Path,
+    Removed Path,
+    Renamed { from : Path, to : Path },
+]
+
+## Tag union of possible errors when watching the filesystem.
+##
+## > This is the same as [`File.IOErr`](File#IOErr).
+IOErr : InternalIOErr.IOErr
+
+## Creates a new [Watcher] that is not watching anything yet, use [watch!] to add paths.
+##
+## This uses the [notify crate](https://docs.rs/notify/latest/notify/), which uses inotify on Linux and FSEvents on macOS.
+new! : {} => Result Watcher [WatcherErr IOErr]
+new! = |{}|
+    Host.watcher_new!({})
+    |> Result.map_ok(@Watcher)
+    |> Result.map_err(|err| WatcherErr(InternalIOErr.handle_err(err)))
+
+## Starts watching a file or directory. With `Recursive` everything inside the directory is watched too,
+## including directories that are created later on.
+##
+## ```
+## watcher = Watcher.new!({})?
+## Watcher.watch!(watcher, "src", Recursive)?
+## ```
+watch! : Watcher, Str, [Recursive, NonRecursive] => Result {} [WatcherErr IOErr]
+watch! = |@Watcher(watcher), path_str, mode|
+    Host.watcher_watch!(watcher, InternalPath.to_bytes(Path.from_str(path_str)), mode == Recursive)
+    |> Result.map_err(|err| WatcherErr(InternalIOErr.handle_err(err)))
+
+## Stops watching a path that was passed to [watch!].
+unwatch! : Watcher, Str => Result {} [WatcherErr IOErr]
+unwatch! = |@Watcher(watcher), path_str|
+    Host.watcher_unwatch!(watcher, InternalPath.to_bytes(Path.from_str(path_str)))
+    |> Result.map_err(|err| WatcherErr(InternalIOErr.handle_err(err)))
+
+## Waits up to `timeout_ms` milliseconds for changes and returns them, an empty list means nothing changed in time.
+##
+## After the first change, this keeps waiting until nothing changed for `debounce_ms` milliseconds.
+## That way saving a file, which is often several writes or a write and a rename, ends up in a single batch.
+## When things keep changing, the batch is returned anyway after ten times `debounce_ms`, or after one second if that is longer.
+## Repeated events for the same path are only returned once per batch.
+##
+## ```
+## rebuild_on_change! = |watcher|
+##     events = Watcher.next!(watcher, { timeout_ms: Num.max_u64, debounce_ms: 100 })?
+##     if !List.is_empty(events) then
+##         Cmd.exec!("roc", ["build", "main.roc"])?
+##     else
+##         {}
+##     rebuild_on_change!(watcher)
+## ```
+next! : Watcher, { timeout_ms : U64, debounce_ms : U64 } => Result (List Event) [WatcherErr IOErr]
+next! = |@Watcher(watcher), { timeout_ms, debounce_ms }|
+    Host.watcher_next!(watcher, timeout_ms, debounce_ms)
+    |> Result.map_ok(|events| List.map(events, from_host_event))
+    |> Result.map_err(|err| WatcherErr(InternalIOErr.handle_err(err)))
+
+from_host_event : InternalPath.InternalWatchEvent -> Event
+from_host_event = |{ from, path, kind }|
+    when kind is
+        Created -> Created(InternalPath.from_os_bytes(path))
+        Modified -> Modified(InternalPath.from_os_bytes(path))
+        Removed -> Removed(InternalPath.from_os_bytes(path))
+        Renamed -> Renamed({ from: InternalPath.from_os_bytes(from), to: InternalPath.from_os_bytes(path) })
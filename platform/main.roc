@@ -19,6 +19,7 @@ platform "cli"
         Locale,
         Sqlite,
         Random,
+        Watcher,
     ]
     packages {}
     imports []
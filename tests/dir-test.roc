@@ -0,0 +1,98 @@
+app [main!] {
+    pf: platform "../platform/main.roc",
+}
+
+import pf.Stdout
+import pf.Dir
+import pf.File
+import pf.Arg exposing [Arg]
+
+main! : List Arg => Result {} _
+main! = |_args|
+    result = run_tests!({})
+
+    # Clean up even when a test failed
+    _ = Dir.delete_all!("test_dir_walk")
+
+    when result is
+        Ok(_) -> Ok({})
+        Err(err) -> Err(Exit(1, "Test run failed:\n\t${Inspect.to_str(err)}"))
+
+run_tests! : {} => Result {} _
+run_tests! = |{}|
+    Stdout.line!("Testing Dir functions...")?
+
+    test_walk_batches!({})?
+    test_walk_errors!({})?
+    test_disk_usage_and_size!({})?
+
+    Stdout.line!("\nAll tests executed.")
+
+test_walk_batches! : {} => Result {} _
+test_walk_batches! = |{}|
+    Stdout.line!("\nTesting Dir.walk! and Dir.walk_next! with more entries than fit in one batch:")?
+
+    Dir.create_all!("test_dir_walk")?
+
+    # One directory and 1030 files, so the walk needs two batches
+    Dir.create!("test_dir_walk/sub")?
+    List.range({ start: At(0), end: Before(1030) })
+    |> List.for_each_try!(|i| File.write_utf8!("", "test_dir_walk/sub/file_${Num.to_str(i)}.txt"))?
+
+    walker = Dir.walk!("test_dir_walk", { max_depth: Unlimited, follow_sym_links: Bool.false, skip_hidden: Bool.false, respect_gitignore: Bool.false })?
+    batch_sizes = walk_batch_sizes!(walker, [])?
+
+    Stdout.line!(
+        """
+        Batch sizes: ${Inspect.to_str(batch_sizes)}
+        Total entries: ${Num.to_str(List.sum(batch_sizes))}
+        """
+    )
+
+walk_batch_sizes! : Dir.Walker, List U64 => Result (List U64) [DirErr Dir.IOErr]
+walk_batch_sizes! = |walker, sizes|
+    when Dir.walk_next!(walker)? is
+        [] -> Ok(sizes)
+        batch -> walk_batch_sizes!(walker, List.append(sizes, List.len(batch)))
+
+test_walk_errors! : {} => Result {} _
+test_walk_errors! = |{}|
+    Stdout.line!("\nTesting Dir.walk! errors:")?
+
+    file_result = Dir.walk!("test_dir_walk/sub/file_0.txt", { max_depth: Unlimited, follow_sym_links: Bool.false, skip_hidden: Bool.false, respect_gitignore: Bool.false })
+    missing_result = Dir.walk!("test_dir_walk/missing", { max_depth: Unlimited, follow_sym_links: Bool.false, skip_hidden: Bool.false, respect_gitignore: Bool.false })
+
+    Stdout.line!(
+        """
+        Walking a file: ${walk_err_str(file_result)}
+        Walking a missing directory: ${walk_err_str(missing_result)}
+        """
+    )
+
+test_disk_usage_and_size! : {} => Result {} _
+test_disk_usage_and_size! = |{}|
+    Stdout.line!("\nTesting Dir.disk_usage! and Dir.size_recursive!:")?
+
+    disk = Dir.disk_usage!("test_dir_walk")?
+
+    Dir.create!("test_dir_walk/size")?
+    File.write_utf8!("twelve bytes", "test_dir_walk/size/original.txt")?
+    File.hard_link!("test_dir_walk/size/original.txt", "test_dir_walk/size/link.txt")?
+
+    size = Dir.size_recursive!("test_dir_walk/size")?
+    # The directory itself counts too, its size depends on the filesystem
+    dir_info = File.metadata!("test_dir_walk/size", NoFollowSymLinks)?
+
+    Stdout.line!(
+        """
+        Total >= free >= available: ${Inspect.to_str(disk.total >= disk.free and disk.free >= disk.available)}
+        Filesystem type is known: ${Inspect.to_str(!Str.is_empty(disk.fs_type) and disk.fs_type != "unknown")}
+        Size of the files, hard links counted once: ${Num.to_str(size.apparent - dir_info.size)}
+        """
+    )
+
+walk_err_str : Result Dir.Walker [DirErr Dir.IOErr] -> Str
+walk_err_str = |result|
+    when result is
+        Ok(_) -> "Ok"
+        Err(DirErr(err)) -> Inspect.to_str(err)
@@ -8,6 +8,10 @@ import pf.Stderr
 import pf.File
 import pf.Arg exposing [Arg]
 import pf.Cmd
+import pf.Utc
+import pf.Path
+import pf.Dir
+import pf.Env
 import json.Json
 
 main! : List Arg => Result {} _
@@ -43,6 +47,39 @@ run_tests! = |{}|
     # Test file exists
     test_file_exists!({})?
 
+    # Test atomic file replace
+    test_write_atomic!({})?
+
+    # Test file metadata
+    test_metadata!({})?
+
+    # Test changing permissions, ownership and timestamps
+    test_set_permissions_owner_times!({})?
+
+    # Test symlink creation, reading and canonicalize
+    test_sym_link!({})?
+
+    # Test copying files
+    test_copy!({})?
+
+    # Test temporary files and directories
+    test_create_temp!({})?
+
+    # Test advisory file locks
+    test_lock!({})?
+
+    # Test memory mapped files
+    test_map_readonly!({})?
+
+    # Test reading into an existing list
+    test_read_into!({})?
+
+    # Test extended attributes
+    test_xattrs!({})?
+
+    # Test changing the length of files and named pipes
+    test_set_len!({})?
+
     Stdout.line!("\nI ran all file function tests.")
 
 test_basic_file_operations! : {} => Result {} _
@@ -240,6 +277,357 @@ test_file_exists! = |{}|
 
     Ok({})
 
+test_write_atomic! : {} => Result {} _
+test_write_atomic! = |{}|
+    Stdout.line!("\nTesting File.write_atomic!:")?
+
+    filename = "test_atomic.txt"
+    File.write_utf8!("Old content", filename)?
+    Cmd.exec!("chmod", ["755", filename])?
+
+    File.write_atomic!(Str.to_utf8("New content"), filename)?
+
+    content = File.read_utf8!(filename)?
+    Stdout.line!("Content after atomic write: ${content}")?
+
+    is_executable = File.is_executable!(filename)?
+    if is_executable then
+        Stdout.line!("✓ File.write_atomic! kept the permissions of the replaced file")?
+    else
+        Stderr.line!("✗ File.write_atomic! did not keep the permissions of the replaced file")?
+
+    File.sym_link!(filename, "test_atomic_link.txt")?
+    File.write_atomic!(Str.to_utf8("Through the link"), "test_atomic_link.txt")?
+
+    link_kept = File.is_sym_link!("test_atomic_link.txt")?
+    target_content = File.read_utf8!(filename)?
+    Stdout.line!("Link kept after atomic write: ${Inspect.to_str(link_kept)}, target content: ${target_content}")?
+
+    Ok({})
+
+test_metadata! : {} => Result {} _
+test_metadata! = |{}|
+    Stdout.line!("\nTesting File.metadata!:")?
+
+    file_info = File.metadata!("test_bytes.txt", FollowSymLinks)?
+    link_info = File.metadata!("test_symlink.txt", NoFollowSymLinks)?
+    followed_info = File.metadata!("test_symlink.txt", FollowSymLinks)?
+
+    Stdout.line!(
+        """
+        test_bytes.txt size: ${Num.to_str(file_info.size)}
+        test_bytes.txt type: ${Inspect.to_str(file_info.type)}
+        test_bytes.txt hard links: ${Num.to_str(file_info.nlink)}
+        test_symlink.txt type without following: ${Inspect.to_str(link_info.type)}
+        test_symlink.txt type when following: ${Inspect.to_str(followed_info.type)}
+        Followed link has the inode of its target: ${Inspect.to_str(followed_info.inode == file_info.inode)}
+        """
+    )?
+
+    Ok({})
+
+test_set_permissions_owner_times! : {} => Result {} _
+test_set_permissions_owner_times! = |{}|
+    Stdout.line!("\nTesting File.set_permissions!, File.chown! and File.set_times!:")?
+
+    filename = "test_permissions.txt"
+    File.write_utf8!("#!/bin/sh", filename)?
+
+    File.set_permissions!(filename, 0o700)?
+    executable_after_700 = File.is_executable!(filename)?
+
+    File.set_permissions!(filename, 0o644)?
+    executable_after_644 = File.is_executable!(filename)?
+    mode = (File.metadata!(filename, FollowSymLinks)?).mode |> Num.bitwise_and(0o777)
+
+    # Changing the owner to the current owner does not need root.
+    owner = (File.metadata!(filename, FollowSymLinks)?).uid
+    File.chown!(filename, Uid(owner), SameGid)?
+
+    two_thousand = Utc.from_millis_since_epoch(946_684_800_000)
+    File.set_times!(filename, { accessed: two_thousand, modified: two_thousand })?
+    modified = File.time_modified!(filename)?
+
+    # The times are set by path, the file does not have to be readable.
+    File.set_permissions!(filename, 0o200)?
+    epoch = Utc.from_millis_since_epoch(0)
+    File.set_times!(filename, { accessed: epoch, modified: epoch })?
+    modified_without_read = File.time_modified!(filename)?
+    File.set_permissions!(filename, 0o644)?
+
+    Stdout.line!(
+        """
+        Executable after 0o700: ${Inspect.to_str(executable_after_700)}
+        Executable after 0o644: ${Inspect.to_str(executable_after_644)}
+        Mode is 0o644: ${Inspect.to_str(mode == 0o644)}
+        ✓ File.chown! to the current owner succeeded
+        Modified time was set: ${Inspect.to_str(Utc.to_millis_since_epoch(modified) == 946_684_800_000)}
+        Modified time was set without read permission: ${Inspect.to_str(Utc.to_millis_since_epoch(modified_without_read) == 0)}
+        """
+    )?
+
+    Ok({})
+
+test_sym_link! : {} => Result {} _
+test_sym_link! = |{}|
+    Stdout.line!("\nTesting File.sym_link!, File.read_link! and File.canonicalize!:")?
+
+    File.sym_link!("test_bytes.txt", "test_sym_link_created.txt")?
+
+    is_sym_link = File.is_sym_link!("test_sym_link_created.txt")?
+    link_target = File.read_link!("test_sym_link_created.txt")?
+    canonical = File.canonicalize!("./test_sym_link_created.txt") |> Result.map_ok(Path.display)?
+
+    Stdout.line!(
+        """
+        Created link is a symbolic link: ${Inspect.to_str(is_sym_link)}
+        Link points to: ${Path.display(link_target)}
+        Canonical path is absolute: ${Inspect.to_str(Str.starts_with(canonical, "/"))}
+        Canonical path resolves the link: ${Inspect.to_str(Str.ends_with(canonical, "/test_bytes.txt"))}
+        """
+    )?
+
+    Ok({})
+
+test_copy! : {} => Result {} _
+test_copy! = |{}|
+    Stdout.line!("\nTesting File.copy!:")?
+
+    File.write_utf8!("Copy me", "test_copy_source.txt")?
+    File.set_permissions!("test_copy_source.txt", 0o750)?
+    two_thousand = Utc.from_millis_since_epoch(946_684_800_000)
+    File.set_times!("test_copy_source.txt", { accessed: two_thousand, modified: two_thousand })?
+
+    bytes_copied = File.copy!(
+        "test_copy_source.txt",
+        "test_copy_dest.txt",
+        { overwrite: FailIfExists, preserve_mode: Bool.true, preserve_times: Bool.true },
+    )?
+    copied_content = File.read_utf8!("test_copy_dest.txt")?
+    copied_info = File.metadata!("test_copy_dest.txt", FollowSymLinks)?
+
+    second_copy = File.copy!(
+        "test_copy_source.txt",
+        "test_copy_dest.txt",
+        { overwrite: FailIfExists, preserve_mode: Bool.false, preserve_times: Bool.false },
+    )
+    skipped_bytes = File.copy!(
+        "test_copy_source.txt",
+        "test_copy_dest.txt",
+        { overwrite: SkipExisting, preserve_mode: Bool.false, preserve_times: Bool.false },
+    )?
+
+    Stdout.line!(
+        """
+        Bytes copied: ${Num.to_str(bytes_copied)}
+        Copied content: ${copied_content}
+        Mode was preserved: ${Inspect.to_str(Num.bitwise_and(copied_info.mode, 0o777) == 0o750)}
+        Modified time was preserved: ${Inspect.to_str(Utc.to_millis_since_epoch(copied_info.modified) == 946_684_800_000)}
+        FailIfExists refused to overwrite: ${Inspect.to_str(Result.is_err(second_copy))}
+        SkipExisting copied bytes: ${Num.to_str(skipped_bytes)}
+        """
+    )?
+
+    Ok({})
+
+test_create_temp! : {} => Result {} _
+test_create_temp! = |{}|
+    Stdout.line!("\nTesting File.create_temp! and Dir.create_temp!:")?
+
+    temp_file = File.create_temp!({ prefix: "basic-cli-test-", suffix: ".txt" })?
+    other_temp_file = File.create_temp!({ prefix: "basic-cli-test-", suffix: ".txt" })?
+    temp_file_str = Path.display(temp_file)
+    file_name = temp_file_str |> Str.split_last("/") |> Result.map_ok(.after) |> Result.with_default("")
+
+    Path.write_utf8!("temporary", temp_file)?
+    temp_content = Path.read_utf8!(temp_file)?
+
+    temp_dir = Dir.create_temp!({ prefix: "basic-cli-test-dir-", suffix: "" })?
+    temp_dir_is_dir = Path.is_dir!(temp_dir)?
+
+    kept_file = File.create_temp!({ prefix: "basic-cli-test-kept-", suffix: "" })?
+    File.keep_temp!(kept_file)
+    Path.delete!(kept_file)?
+
+    invalid_prefix = File.create_temp!({ prefix: "nested/", suffix: "" })
+
+    Stdout.line!(
+        """
+        Temp file is in the temp dir: ${Inspect.to_str(Str.starts_with(temp_file_str, Path.display(Env.temp_dir!({}))))}
+        Temp file name length: ${Num.to_str(Str.count_utf8_bytes(file_name))}
+        Temp file names are unique: ${Inspect.to_str(temp_file_str != Path.display(other_temp_file))}
+        Temp file content: ${temp_content}
+        Temp dir is a directory: ${Inspect.to_str(temp_dir_is_dir)}
+        Prefix with a path separator is rejected: ${Inspect.to_str(Result.is_err(invalid_prefix))}
+        Temp file path: ${temp_file_str}
+        Temp dir path: ${Path.display(temp_dir)}
+        """
+    )?
+
+    Ok({})
+
+test_lock! : {} => Result {} _
+test_lock! = |{}|
+    Stdout.line!("\nTesting File.lock!, File.try_lock!, File.lock_shared! and File.unlock!:")?
+
+    lock_file = "test_lock.lock"
+
+    exclusive = File.lock!(lock_file)?
+    blocked_by_exclusive = File.try_lock!(lock_file)
+    File.unlock!(exclusive)?
+    after_unlock = File.try_lock!(lock_file)?
+    File.unlock!(after_unlock)?
+
+    shared = File.lock_shared!(lock_file)?
+    other_shared = File.lock_shared!(lock_file)?
+    blocked_by_shared = File.try_lock!(lock_file)
+    File.unlock!(shared)?
+    File.unlock!(other_shared)?
+
+    # The lock inside is released when the helper returns
+    lock_and_forget!(lock_file)?
+    after_drop = File.try_lock!(lock_file)?
+    File.unlock!(after_drop)?
+
+    Stdout.line!(
+        """
+        try_lock! while locked: ${already_locked_str(blocked_by_exclusive)}
+        try_lock! after unlock!: ✓
+        Two shared locks at the same time: ✓
+        try_lock! while shared locks are held: ${already_locked_str(blocked_by_shared)}
+        try_lock! after the lock was dropped: ✓
+        """
+    )?
+
+    Ok({})
+
+lock_and_forget! : Str => Result {} _
+lock_and_forget! = |path_str|
+    _ = File.lock!(path_str)?
+    Ok({})
+
+already_locked_str : Result File.Lock [AlreadyLocked Path.Path, LockErr Path.Path File.IOErr] -> Str
+already_locked_str = |result|
+    when result is
+        Err(AlreadyLocked(_)) -> "AlreadyLocked"
+        Err(LockErr(_, err)) -> "LockErr ${Inspect.to_str(err)}"
+        Ok(_) -> "Ok"
+
+test_map_readonly! : {} => Result {} _
+test_map_readonly! = |{}|
+    Stdout.line!("\nTesting File.map_readonly!, File.mapped_len and File.mapped_slice!:")?
+
+    File.write_utf8!("0123456789abcdef", "test_mapped.txt")?
+    mapped = File.map_readonly!("test_mapped.txt")?
+
+    middle = File.mapped_slice!(mapped, 10, 3) |> Str.from_utf8 |> Result.with_default("")
+    past_end = File.mapped_slice!(mapped, 14, 100) |> Str.from_utf8 |> Result.with_default("")
+    beyond = File.mapped_slice!(mapped, 100, 5)
+
+    File.write_utf8!("", "test_mapped_empty.txt")?
+    mapped_empty = File.map_readonly!("test_mapped_empty.txt")?
+
+    Stdout.line!(
+        """
+        Mapped length: ${Num.to_str(File.mapped_len(mapped))}
+        Slice at offset 10: ${middle}
+        Slice past the end: ${past_end}
+        Slice beyond the end length: ${Num.to_str(List.len(beyond))}
+        Empty file length: ${Num.to_str(File.mapped_len(mapped_empty))}
+        """
+    )?
+
+    Ok({})
+
+test_read_into! : {} => Result {} _
+test_read_into! = |{}|
+    Stdout.line!("\nTesting File.read_into!:")?
+
+    File.write_utf8!("hello world", "test_read_into.txt")?
+    File.write_utf8!("bye", "test_read_into_2.txt")?
+    first = File.read_into!("test_read_into.txt", List.with_capacity(64))?
+    Stdout.line!("First read: ${Str.from_utf8(first) |> Result.with_default("")}")?
+
+    # The list from the first read is passed on, so the second read reuses its allocation
+    second = File.read_into!("test_read_into_2.txt", first)?
+    replaced = File.read_into!("test_read_into.txt", Str.to_utf8("old contents "))?
+
+    Stdout.line!(
+        """
+        Second read into the same list: ${Str.from_utf8(second) |> Result.with_default("")}
+        Read into a non-empty list: ${Str.from_utf8(replaced) |> Result.with_default("")}
+        """
+    )?
+
+    Ok({})
+
+test_xattrs! : {} => Result {} _
+test_xattrs! = |{}|
+    Stdout.line!("\nTesting File.set_xattr!, File.get_xattr!, File.list_xattrs! and File.remove_xattr!:")?
+
+    File.write_utf8!("artifact", "test_xattrs.txt")?
+    File.set_xattr!("test_xattrs.txt", "user.sha256", Str.to_utf8("abc123"))?
+    File.set_xattr!("test_xattrs.txt", "user.origin", Str.to_utf8("ci"))?
+
+    checksum = File.get_xattr!("test_xattrs.txt", "user.sha256")?
+    names = File.list_xattrs!("test_xattrs.txt")?
+    File.remove_xattr!("test_xattrs.txt", "user.origin")?
+    removed = File.get_xattr!("test_xattrs.txt", "user.origin")
+    names_after_remove = File.list_xattrs!("test_xattrs.txt")?
+    missing_file = File.get_xattr!("test_xattrs_missing.txt", "user.sha256")
+    removed_twice = File.remove_xattr!("test_xattrs.txt", "user.origin")
+
+    Stdout.line!(
+        """
+        user.sha256: ${Str.from_utf8(checksum) |> Result.with_default("")}
+        Listed both attributes: ${Inspect.to_str(List.contains(names, "user.sha256") and List.contains(names, "user.origin"))}
+        Reading a removed attribute: ${xattr_not_found_str(removed)}
+        Listed after remove: ${Inspect.to_str(List.contains(names_after_remove, "user.origin"))}
+        Reading from a missing file: ${xattr_not_found_str(missing_file)}
+        Removing a removed attribute: ${remove_xattr_str(removed_twice)}
+        """
+    )?
+
+    Ok({})
+
+test_set_len! : {} => Result {} _
+test_set_len! = |{}|
+    Stdout.line!("\nTesting File.set_len!, File.truncate! and File.create_fifo!:")?
+
+    File.write_utf8!("abcdef", "test_set_len.txt")?
+    File.set_len!("test_set_len.txt", 10)?
+    grown = File.read_bytes!("test_set_len.txt")?
+    File.truncate!("test_set_len.txt", 3)?
+    File.truncate!("test_set_len.txt", 100)?
+    trimmed = File.read_utf8!("test_set_len.txt")?
+
+    File.create_fifo!("test_fifo", 0o600)?
+    fifo_info = File.metadata!("test_fifo", NoFollowSymLinks)?
+
+    Stdout.line!(
+        """
+        Grown bytes: ${Inspect.to_str(grown)}
+        After truncate!: ${trimmed}
+        Fifo type: ${Inspect.to_str(fifo_info.type)}
+        """
+    )?
+
+    Ok({})
+
+xattr_not_found_str : Result (List U8) [XattrNotFound Str, FileReadErr Path.Path File.IOErr] -> Str
+xattr_not_found_str = |result|
+    when result is
+        Err(XattrNotFound(name)) -> "XattrNotFound ${name}"
+        Err(FileReadErr(_, err)) -> "FileReadErr ${Inspect.to_str(err)}"
+        Ok(_) -> "Ok"
+
+remove_xattr_str : Result {} [XattrNotFound Str, FileWriteErr Path.Path File.IOErr] -> Str
+remove_xattr_str = |result|
+    when result is
+        Err(XattrNotFound(name)) -> "XattrNotFound ${name}"
+        Err(FileWriteErr(_, err)) -> "FileWriteErr ${Inspect.to_str(err)}"
+        Ok({}) -> "Ok"
+
 cleanup_test_files! : [FilesNeedToExist, FilesMaybeExist] => Result {} _
 cleanup_test_files! = |files_requirement|
     Stdout.line!("\nCleaning up test files...")?
@@ -247,11 +635,25 @@ cleanup_test_files! = |files_requirement|
     test_files = [
         "test_bytes.txt",
         "test_symlink.txt",
+        "test_atomic_link.txt",
         "test_write.json", 
         "test_multiline.txt",
         "test_original_file.txt",
         "test_link_to_original.txt",
         "test_rename_new.txt",
+        "test_atomic.txt",
+        "test_permissions.txt",
+        "test_sym_link_created.txt",
+        "test_copy_source.txt",
+        "test_copy_dest.txt",
+        "test_lock.lock",
+        "test_mapped.txt",
+        "test_mapped_empty.txt",
+        "test_read_into.txt",
+        "test_read_into_2.txt",
+        "test_xattrs.txt",
+        "test_set_len.txt",
+        "test_fifo",
     ]
 
     delete_result = List.for_each_try!(
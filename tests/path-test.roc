@@ -6,8 +6,10 @@ app [main!] {
 import pf.Stdout
 import pf.Stderr
 import pf.Path
+import pf.Dir
 import pf.Arg exposing [Arg]
 import pf.Cmd
+import pf.Utc
 import json.Json
 
 main! : List Arg => Result {} _
@@ -47,6 +49,15 @@ run_tests! = |{}|
     # Test path exists
     test_path_exists!({})?
 
+    # Test symbolic links
+    test_path_sym_link!({})?
+
+    # Test copying files and directories
+    test_path_copy!({})?
+
+    # Test glob patterns
+    test_path_glob!({})?
+
     Stdout.line!("\nI ran all Path function tests.")
 
 test_path_creation! : {} => Result {} _
@@ -106,6 +117,10 @@ test_file_operations! = |{}|
         """
     )?
 
+    # Test Path.read_into!
+    read_twice = Path.read_into!(bytes_path, read_bytes)?
+    Stdout.line!("Path.read_into! appended the file: ${Inspect.to_str(read_twice == List.concat(test_bytes, test_bytes))}")?
+
     # Test Path.write_utf8! and Path.read_utf8!
     utf8_content = "Hello from Path module! 🚀"
     utf8_path = Path.from_str("test_path_utf8.txt")
@@ -144,6 +159,32 @@ test_file_operations! = |{}|
         """
     )?
 
+    # Test Path.write_atomic!
+    atomic_path = Path.from_str("test_path_atomic.txt")
+    Path.write_atomic!(Str.to_utf8("Atomic content"), atomic_path)?
+
+    atomic_content = Path.read_utf8!(atomic_path)?
+    Path.delete!(atomic_path)?
+
+    Stdout.line!("Atomic write content: ${atomic_content}")?
+
+    # Test Path.set_permissions!, Path.chown! and Path.set_times!
+    script_path = Path.from_str("test_path_script.sh")
+    Path.write_utf8!("#!/bin/sh", script_path)?
+    Path.set_permissions!(script_path, 0o755)?
+    Path.chown!(script_path, SameUid, SameGid)?
+    Path.set_times!(script_path, { accessed: Utc.from_millis_since_epoch(0), modified: Utc.from_millis_since_epoch(0) })?
+
+    script_info = Path.metadata!(script_path, FollowSymLinks)?
+    Path.delete!(script_path)?
+
+    Stdout.line!(
+        """
+        Script mode: ${Num.to_str(Num.bitwise_and(script_info.mode, 0o777))}
+        Script modified at epoch: ${Inspect.to_str(Utc.to_millis_since_epoch(script_info.modified) == 0)}
+        """
+    )?
+
     # Test Path.delete!
     delete_path = Path.from_str("test_to_delete.txt")
     Path.write_utf8!("This file will be deleted", delete_path)?
@@ -175,6 +216,9 @@ test_directory_operations! = |{}|
     # Verify directory exists
     _ = Cmd.exec!("test", ["-d", "test_single_dir"])?
 
+    single_dir_info = Path.metadata!(single_dir, NoFollowSymLinks)?
+    Stdout.line!("Path.metadata! type of test_single_dir: ${Inspect.to_str(single_dir_info.type)}")?
+
     # Test Path.create_all! (nested directories)
     nested_dir = Path.from_str("test_parent/test_child/test_grandchild")
     Path.create_all!(nested_dir)?
@@ -390,6 +434,174 @@ test_path_exists! = |{}|
 
     Ok({})
 
+test_path_sym_link! : {} => Result {} _
+test_path_sym_link! = |{}|
+    Stdout.line!("\nTesting Path.sym_link!:")?
+
+    Path.create_all!(Path.from_str("test_path_link_dir/sub"))?
+    Path.sym_link!(Path.from_str("sub"), Path.from_str("test_path_link_dir/link"))?
+
+    link_target = Path.read_link!(Path.from_str("test_path_link_dir/link"))?
+    canonical_via_link = Path.canonicalize!(Path.from_str("test_path_link_dir/./link/../sub"))?
+    canonical_sub = Path.canonicalize!(Path.from_str("test_path_link_dir/sub"))?
+
+    Path.delete_all!(Path.from_str("test_path_link_dir"))?
+
+    Stdout.line!(
+        """
+        Link points to: ${Path.display(link_target)}
+        Canonicalize resolves links, . and ..: ${Inspect.to_str(Path.display(canonical_via_link) == Path.display(canonical_sub))}
+        """
+    )
+
+test_path_copy! : {} => Result {} _
+test_path_copy! = |{}|
+    Stdout.line!("\nTesting Path.copy! and Path.copy_all!:")?
+
+    Path.create_all!(Path.from_str("test_path_copy_src/nested"))?
+    Path.write_utf8!("top", Path.from_str("test_path_copy_src/top.txt"))?
+    Path.write_utf8!("deep", Path.from_str("test_path_copy_src/nested/deep.txt"))?
+    Path.sym_link!(Path.from_str("top.txt"), Path.from_str("test_path_copy_src/link.txt"))?
+
+    bytes_copied = Path.copy!(
+        Path.from_str("test_path_copy_src/top.txt"),
+        Path.from_str("test_path_copy_src/top_copy.txt"),
+        { overwrite: Overwrite, preserve_mode: Bool.true, preserve_times: Bool.false },
+    )?
+
+    Path.copy_all!(
+        Path.from_str("test_path_copy_src"),
+        Path.from_str("test_path_copy_dst"),
+        { overwrite: FailIfExists, preserve_mode: Bool.true, preserve_times: Bool.true, sym_links: CopyLinks },
+    )?
+
+    deep_content = Path.read_utf8!(Path.from_str("test_path_copy_dst/nested/deep.txt"))?
+    link_is_sym_link = Path.is_sym_link!(Path.from_str("test_path_copy_dst/link.txt"))?
+    link_target = Path.read_link!(Path.from_str("test_path_copy_dst/link.txt"))?
+
+    Path.copy_all!(
+        Path.from_str("test_path_copy_src"),
+        Path.from_str("test_path_copy_skip"),
+        { overwrite: FailIfExists, preserve_mode: Bool.false, preserve_times: Bool.false, sym_links: SkipLinks },
+    )?
+    skipped_link_exists = Path.exists!(Path.from_str("test_path_copy_skip/link.txt"))?
+
+    copy_onto_itself = Path.copy!(
+        Path.from_str("test_path_copy_src/top.txt"),
+        Path.from_str("test_path_copy_src/link.txt"),
+        { overwrite: Overwrite, preserve_mode: Bool.false, preserve_times: Bool.false },
+    )
+    top_content = Path.read_utf8!(Path.from_str("test_path_copy_src/top.txt"))?
+
+    copy_into_itself = Path.copy_all!(
+        Path.from_str("test_path_copy_src"),
+        Path.from_str("test_path_copy_src/nested/copy"),
+        { overwrite: FailIfExists, preserve_mode: Bool.false, preserve_times: Bool.false, sym_links: CopyLinks },
+    )
+    nested_copy_exists = Path.exists!(Path.from_str("test_path_copy_src/nested/copy"))?
+
+    Path.delete_all!(Path.from_str("test_path_copy_src"))?
+    Path.delete_all!(Path.from_str("test_path_copy_dst"))?
+    Path.delete_all!(Path.from_str("test_path_copy_skip"))?
+
+    Stdout.line!(
+        """
+        Path.copy! bytes copied: ${Num.to_str(bytes_copied)}
+        Nested file content after copy_all: ${deep_content}
+        CopyLinks kept the link: ${Inspect.to_str(link_is_sym_link)}
+        Copied link points to: ${Path.display(link_target)}
+        SkipLinks left the link out: ${Inspect.to_str(!skipped_link_exists)}
+        Copying a file onto itself failed: ${Inspect.to_str(Result.is_err(copy_onto_itself))}, content kept: ${top_content}
+        Copying a directory into itself failed: ${Inspect.to_str(Result.is_err(copy_into_itself))}, nothing created: ${Inspect.to_str(!nested_copy_exists)}
+        """
+    )
+
+test_path_glob! : {} => Result {} _
+test_path_glob! = |{}|
+    Stdout.line!("\nTesting Path.glob! and Path.matches_glob:")?
+
+    Path.create_all!(Path.from_str("test_path_glob/src/parser"))?
+    Path.write_utf8!("", Path.from_str("test_path_glob/src/main.roc"))?
+    Path.write_utf8!("", Path.from_str("test_path_glob/src/parser/Lexer.roc"))?
+    Path.write_utf8!("", Path.from_str("test_path_glob/src/parser/lexer.rs"))?
+    Path.write_utf8!("", Path.from_str("test_path_glob/README.md"))?
+    Path.write_utf8!("", Path.from_str("test_path_glob/.hidden.roc"))?
+    Path.sym_link!(Path.from_str("src/parser"), Path.from_str("test_path_glob/linked"))?
+
+    all_roc = Path.glob!("test_path_glob/**/*.roc")?
+    top_level = Path.glob!("test_path_glob/src/*.{roc,rs}")?
+    classes = Path.glob!("test_path_glob/src/parser/[A-Z]*.?o?")?
+    no_dir = Path.glob!("test_path_glob/missing/**/*")?
+    not_hidden = Path.glob!("test_path_glob/**/[!.]*.roc")?
+
+    # Both matchers have to agree on every path in the tree
+    all_paths = walk_paths!(Dir.walk!("test_path_glob", { max_depth: Unlimited, follow_sym_links: Bool.true, skip_hidden: Bool.false, respect_gitignore: Bool.false })?, [])?
+    disagreeing_patterns = List.walk_try!(
+        [
+            "test_path_glob/**/*.roc",
+            "test_path_glob/**",
+            "test_path_glob/*",
+            "test_path_glob/src/*.{roc,rs}",
+            "test_path_glob/src/parser/[A-Z]*.?o?",
+            "test_path_glob/src/[!m]*",
+            "test_path_glob/**/.hidden*",
+            "test_path_glob/[!.]*",
+            "test_path_glob/linked/*.{rs,roc}",
+            "test_path_glob/**/parser/**/*",
+            "test_path_glob/?EADME.md",
+        ],
+        [],
+        |disagreeing, pattern|
+            { matches } = Path.glob!(pattern)?
+            expected = List.keep_if(all_paths, |path| Path.matches_glob(path, pattern)) |> List.map(Path.display)
+
+            if List.map(matches, Path.display) |> List.sort_with(compare_strs) == List.sort_with(expected, compare_strs) then
+                Ok(disagreeing)
+            else
+                Ok(List.append(disagreeing, pattern)),
+    )?
+
+    Path.delete_all!(Path.from_str("test_path_glob"))?
+
+    display_all = |paths| paths |> List.map(Path.display) |> Str.join_with(", ")
+
+    Stdout.line!(
+        """
+        All .roc files: ${display_all(all_roc.matches)}
+        Top level sources: ${display_all(top_level.matches)}
+        Character classes: ${display_all(classes.matches)}
+        Matches in a missing directory: ${Num.to_str(List.len(no_dir.matches))}
+        Unreadable directories: ${Num.to_str(List.len(all_roc.unreadable))}
+        Leaving out hidden files: ${display_all(not_hidden.matches)}
+        Patterns where glob! and matches_glob disagree: ${Inspect.to_str(disagreeing_patterns)}
+        matches_glob with **: ${Inspect.to_str(Path.matches_glob(Path.from_str("src/a/b/c.roc"), "src/**/*.roc"))}
+        matches_glob with * across directories: ${Inspect.to_str(Path.matches_glob(Path.from_str("src/a/c.roc"), "src/*.roc"))}
+        """
+    )
+
+walk_paths! : Dir.Walker, List Path.Path => Result (List Path.Path) [DirErr Dir.IOErr]
+walk_paths! = |walker, paths|
+    when Dir.walk_next!(walker)? is
+        [] -> Ok(paths)
+        batch -> walk_paths!(walker, List.concat(paths, List.map(batch, .path)))
+
+compare_strs : Str, Str -> [LT, EQ, GT]
+compare_strs = |a, b| compare_bytes(Str.to_utf8(a), Str.to_utf8(b))
+
+compare_bytes : List U8, List U8 -> [LT, EQ, GT]
+compare_bytes = |a, b|
+    when (a, b) is
+        ([], []) -> EQ
+        ([], _) -> LT
+        (_, []) -> GT
+        ([x, .. as a_rest], [y, .. as b_rest]) ->
+            if x < y then
+                LT
+            else if x > y then
+                GT
+            else
+                compare_bytes(a_rest, b_rest)
+
 cleanup_test_files! : [FilesNeedToExist, FilesMaybeExist] => Result {} _
 cleanup_test_files! = |files_requirement|
     Stdout.line!("\nCleaning up test files...")?